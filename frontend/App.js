@@ -12,6 +12,11 @@ const BOATLOAD_OF_GAS = Big(3)
   .times(10 ** 13)
   .toFixed();
 
+// Covers about 1 KB of contract storage; unused deposit stays in the storage balance
+const STORAGE_DEPOSIT = Big(1)
+  .times(10 ** 22)
+  .toFixed();
+
 const App = ({ contract, currentUser, nearConfig, wallet }) => {
   const [status, setStatus] = useState(null);
   const [statusHistory, setStatusHistory] = useState([]);
@@ -34,6 +39,7 @@ const App = ({ contract, currentUser, nearConfig, wallet }) => {
     try {
       const status = await contract.get_status({
         account_id: currentUser.accountId,
+        viewer_id: currentUser.accountId,
       });
       setStatus(status);
       setError(null);
@@ -48,8 +54,9 @@ const App = ({ contract, currentUser, nearConfig, wallet }) => {
     try {
       const history = await contract.get_status_history({
         account_id: currentUser.accountId,
+        viewer_id: currentUser.accountId,
       });
-      setStatusHistory(history || []);
+      setStatusHistory(history ? history.records : []);
       setError(null);
     } catch (err) {
       console.error('Failed to fetch status history:', err);
@@ -59,8 +66,8 @@ const App = ({ contract, currentUser, nearConfig, wallet }) => {
 
   const fetchPublicStatuses = async () => {
     try {
-      const statuses = await contract.get_public_statuses();
-      setPublicStatuses(statuses || []);
+      const page = await contract.get_public_statuses({});
+      setPublicStatuses(page ? page.statuses : []);
       setError(null);
     } catch (err) {
       console.error('Failed to fetch public statuses:', err);
@@ -165,7 +172,8 @@ const App = ({ contract, currentUser, nearConfig, wallet }) => {
           is_public: isPublic,
           expires_in_hours: expiresInHours > 0 ? expiresInHours : null
         },
-        BOATLOAD_OF_GAS
+        BOATLOAD_OF_GAS,
+        STORAGE_DEPOSIT
       );
       
       await fetchStatus();
@@ -209,7 +217,8 @@ const App = ({ contract, currentUser, nearConfig, wallet }) => {
           name: profileData.name,
           bio: profileData.bio
         },
-        BOATLOAD_OF_GAS
+        BOATLOAD_OF_GAS,
+        STORAGE_DEPOSIT
       );
       
       setError(null);
@@ -230,7 +239,8 @@ const App = ({ contract, currentUser, nearConfig, wallet }) => {
         {
           account_id: accountId
         },
-        BOATLOAD_OF_GAS
+        BOATLOAD_OF_GAS,
+        STORAGE_DEPOSIT
       );
       
       await fetchFollowers();
@@ -276,10 +286,10 @@ const App = ({ contract, currentUser, nearConfig, wallet }) => {
     setError(null);
     
     try {
-      const results = await contract.search_status({
+      const page = await contract.search_status({
         query: searchQuery
       });
-      setSearchResults(results || []);
+      setSearchResults(page ? page.statuses : []);
       setError(null);
     } catch (err) {
       console.error('Failed to search statuses:', err);
@@ -299,7 +309,8 @@ const App = ({ contract, currentUser, nearConfig, wallet }) => {
           account_id: accountId,
           reaction_type: reactionType
         },
-        BOATLOAD_OF_GAS
+        BOATLOAD_OF_GAS,
+        STORAGE_DEPOSIT
       );
       
       // Refresh public statuses to show updated reactions
@@ -333,7 +344,8 @@ const App = ({ contract, currentUser, nearConfig, wallet }) => {
         {
           account_id: accountId
         },
-        BOATLOAD_OF_GAS
+        BOATLOAD_OF_GAS,
+        STORAGE_DEPOSIT
       );
     } catch (err) {
       console.error('Failed to record view:', err);
@@ -482,7 +494,7 @@ const App = ({ contract, currentUser, nearConfig, wallet }) => {
               <div className="current-status">
                 <h3>Your current status:</h3>
                 <p>
-                  <code>{status}</code>
+                  <code>{status.message}</code>
                 </p>
                 <p className="status-privacy">
                   Status is {isPublic ? 'public' : 'private'}
@@ -552,12 +564,12 @@ const App = ({ contract, currentUser, nearConfig, wallet }) => {
             <div className="search-results">
               <h3>Search Results</h3>
               <ul>
-                {searchResults.map(([accountId, message], index) => (
+                {searchResults.map(([accountId, record], index) => (
                   <li key={index} className="feed-item">
                     <div className="feed-header">
                       <strong>{accountId}</strong>
                     </div>
-                    <p>{message}</p>
+                    <p>{record.message}</p>
                   </li>
                 ))}
               </ul>
@@ -7,7 +7,7 @@ export default function StatusFeed({ statuses, onFollow, onAddReaction, onViewSt
       <h2>Public Status Feed</h2>
       {statuses.length > 0 ? (
         <ul>
-          {statuses.map(([accountId, message], index) => (
+          {statuses.map(([accountId, record], index) => (
             <li key={index} className="feed-item">
               <div className="feed-header">
                 <strong>{accountId}</strong>
@@ -25,7 +25,7 @@ export default function StatusFeed({ statuses, onFollow, onAddReaction, onViewSt
                 className="status-message"
                 onClick={() => onViewStatus(accountId)}
               >
-                {message}
+                {record.message}
               </p>
               <div className="reactions">
                 <button 
@@ -0,0 +1,51 @@
+//! Media attachments module
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::env;
+use serde::{Deserialize, Serialize};
+
+pub const MAX_ATTACHMENTS: usize = 4; // default for Config::max_attachments
+pub const MAX_ATTACHMENT_SIZE: u64 = 100 * 1024 * 1024; // 100 MiB
+const MAX_CONTENT_LENGTH: usize = 512;
+const MAX_MIME_TYPE_LENGTH: usize = 128;
+const MAX_ALT_TEXT_LENGTH: usize = 1000;
+const MAX_HASH_LENGTH: usize = 128;
+
+/// Media stored off-chain and referenced from a status.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Attachment {
+    pub content: String, // content id (e.g. IPFS CID) or URL
+    pub mime_type: String,
+    pub size: u64, // bytes
+    pub alt_text: Option<String>,
+    pub content_hash: Option<String>,
+}
+
+impl Attachment {
+    fn validate(&self) {
+        if self.content.is_empty() || self.content.len() > MAX_CONTENT_LENGTH {
+            env::panic_str("Attachment content must be a content id or URL of at most 512 bytes");
+        }
+        if !self.mime_type.contains('/') || self.mime_type.len() > MAX_MIME_TYPE_LENGTH {
+            env::panic_str("Attachment mime type is invalid");
+        }
+        if self.size == 0 || self.size > MAX_ATTACHMENT_SIZE {
+            env::panic_str("Attachment size must be between 1 byte and 100 MiB");
+        }
+        if self.alt_text.as_ref().map_or(false, |alt| alt.len() > MAX_ALT_TEXT_LENGTH) {
+            env::panic_str("Attachment alt text is too long");
+        }
+        if self.content_hash.as_ref().map_or(false, |hash| hash.is_empty() || hash.len() > MAX_HASH_LENGTH) {
+            env::panic_str("Attachment content hash is invalid");
+        }
+    }
+}
+
+pub fn validate_attachments(attachments: &[Attachment], max_attachments: u64) {
+    if attachments.len() as u64 > max_attachments {
+        env::panic_str(&format!("A status can have at most {} attachments", max_attachments));
+    }
+    for attachment in attachments {
+        attachment.validate();
+    }
+}
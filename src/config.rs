@@ -0,0 +1,66 @@
+//! Contract configuration module
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::env;
+use serde::{Deserialize, Serialize};
+
+use crate::attachments::MAX_ATTACHMENTS;
+
+/// Limits the contract enforces on what accounts can store.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Config {
+    pub max_message_bytes: u64,
+    pub max_bio_bytes: u64,
+    pub max_name_bytes: u64, // profile names and list names
+    pub max_reaction_bytes: u64,
+    pub max_attachments: u64,
+    pub max_history_entries: u64, // older entries are pruned past this
+    pub max_notifications: u64, // older notifications are dropped past this
+    pub max_expiry_hours: u64,
+    pub max_pinned: u64,
+    pub restore_window_hours: u64, // how long deleted statuses can be restored for
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_message_bytes: 1024,
+            max_bio_bytes: 512,
+            max_name_bytes: 64,
+            max_reaction_bytes: 32,
+            max_attachments: MAX_ATTACHMENTS as u64,
+            max_history_entries: 1000,
+            max_notifications: 100,
+            max_expiry_hours: 24 * 30,
+            max_pinned: 3,
+            restore_window_hours: 24,
+        }
+    }
+}
+
+impl Config {
+    /// The restore window in nanoseconds. Only valid once `validate` has passed.
+    pub fn restore_window(&self) -> u64 {
+        self.restore_window_hours * 3600 * 1_000_000_000
+    }
+    
+    pub fn validate(&self) {
+        if self.max_message_bytes == 0
+            || self.max_bio_bytes == 0
+            || self.max_name_bytes == 0
+            || self.max_reaction_bytes == 0
+            || self.max_history_entries == 0
+            || self.max_notifications == 0
+            || self.max_expiry_hours == 0
+        {
+            env::panic_str("Config limits must be greater than zero");
+        }
+        // Keeps expiry timestamps from overflowing
+        if self.max_expiry_hours.checked_mul(3600 * 1_000_000_000).is_none() {
+            env::panic_str("max_expiry_hours is too large");
+        }
+        if self.restore_window_hours.checked_mul(3600 * 1_000_000_000).is_none() {
+            env::panic_str("restore_window_hours is too large");
+        }
+    }
+}
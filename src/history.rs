@@ -3,23 +3,137 @@ use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::{env, AccountId};
 use serde::{Deserialize, Serialize};
 
+use crate::attachments::Attachment;
+use crate::ring_buffer::RingBuffer;
+use crate::visibility::Visibility;
+
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct StatusRecord {
+    pub id: String,
+    pub seq: u64,
     pub message: String,
     pub timestamp: u64,
     pub expires_at: Option<u64>,
+    pub edited: bool,
+    pub reply_to: Option<String>, // parent status_id
+    pub reply_count: u64,
+    pub repost_of: Option<String>, // original status_id
+    pub repost_count: u64,
+    pub attachments: Vec<Attachment>,
+    pub visibility: Visibility,
+    pub deleted: Option<Tombstone>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum DeletedBy {
+    Author,
+    Moderator(String),
+}
+
+/// Left in place of a deleted status so readers see a deletion instead of a gap.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Tombstone {
+    pub deleted_at: u64,
+    pub deleted_by: DeletedBy,
+    pub was_current: bool, // whether it was the author's current status
+    pub pin_position: Option<u64>, // where it was in the author's pins
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StatusRevision {
+    pub message: String,
+    pub timestamp: u64,
+}
+
+pub const NANOS_PER_DAY: u64 = 86_400 * 1_000_000_000;
+
+/// How much of an account's history is kept. Older entries are pruned on write.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum RetentionPolicy {
+    KeepLast(u64),
+    KeepDays(u64),
+}
+
+impl RetentionPolicy {
+    pub fn is_valid(&self) -> bool {
+        match self {
+            RetentionPolicy::KeepLast(count) => *count > 0,
+            RetentionPolicy::KeepDays(days) => *days > 0 && days.checked_mul(NANOS_PER_DAY).is_some(),
+        }
+    }
+    
+    /// Whether `record`, the next entry to prune from a history of `len` entries, falls outside
+    /// the policy.
+    pub fn prunes(&self, record: &StatusRecord, len: u64, now: u64) -> bool {
+        match self {
+            RetentionPolicy::KeepLast(count) => len > *count,
+            RetentionPolicy::KeepDays(days) => record.timestamp < now.saturating_sub(days * NANOS_PER_DAY),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// One page of statuses. Pass `next_index` back as the cursor to continue.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StatusPage {
+    pub records: Vec<StatusRecord>,
+    pub next_index: Option<u64>,
+}
+
+/// One page of accounts and their current statuses. `total` counts the accounts that may
+/// have a public status; pass `next_index` back as `from_index` to continue.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountStatusPage {
+    pub statuses: Vec<(String, StatusRecord)>,
+    pub total: u64,
+    pub next_index: Option<u64>,
 }
 
 impl StatusRecord {
-    pub fn new(message: String, expires_at: Option<u64>) -> Self {
+    pub fn new(account_id: &str, seq: u64, message: String, expires_at: Option<u64>) -> Self {
         Self {
+            id: status_id(account_id, seq),
+            seq,
             message,
             timestamp: env::block_timestamp(),
             expires_at,
+            edited: false,
+            reply_to: None,
+            reply_count: 0,
+            repost_of: None,
+            repost_count: 0,
+            attachments: vec![],
+            visibility: Visibility::Public,
+            deleted: None,
         }
     }
     
+    pub fn is_deleted(&self) -> bool {
+        self.deleted.is_some()
+    }
+    
+    /// What readers see: a deleted status keeps its id and tombstone but not its content.
+    pub fn redacted(mut self) -> Self {
+        if self.is_deleted() {
+            self.message = String::new();
+            self.attachments = vec![];
+        }
+        self
+    }
+    
     pub fn is_expired(&self) -> bool {
         if let Some(expiry) = self.expires_at {
             env::block_timestamp() > expiry
@@ -27,4 +141,34 @@ impl StatusRecord {
             false
         }
     }
-}
\ No newline at end of file
+}
+
+/// Builds the stable id of a status: the author's account plus their per-account sequence number.
+pub fn status_id(account_id: &str, seq: u64) -> String {
+    format!("{}:{}", account_id, seq)
+}
+
+/// Splits a status id back into the author's account and sequence number.
+pub fn parse_status_id(status_id: &str) -> Option<(String, u64)> {
+    let (account_id, seq) = status_id.rsplit_once(':')?;
+    if account_id.is_empty() {
+        return None;
+    }
+    seq.parse().ok().map(|seq| (account_id.to_string(), seq))
+}
+
+/// Index of the first entry for which `pred` is false, assuming `pred` holds for a prefix
+/// of the history. History is appended in both sequence and timestamp order, so this
+/// finds entries by `seq` or by time without scanning.
+pub fn partition_point<P: Fn(&StatusRecord) -> bool>(history: &RingBuffer<StatusRecord>, pred: P) -> u64 {
+    let (mut low, mut high) = (0, history.len());
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if pred(&history.get(mid).unwrap()) {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
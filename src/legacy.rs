@@ -0,0 +1,44 @@
+//! State layout from before statuses had ids, read by `migrate`
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, UnorderedMap, UnorderedSet, Vector};
+
+use crate::profile::UserProfile;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct OldStatusRecord {
+    pub message: String,
+    pub timestamp: u64,
+    pub expires_at: Option<u64>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct OldReaction {
+    pub account_id: String,
+    pub reaction_type: String,
+    pub timestamp: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct OldStatusMessage {
+    pub records: LookupMap<String, String>,
+    pub history: LookupMap<String, Vector<OldStatusRecord>>,
+    pub profiles: LookupMap<String, UserProfile>,
+    pub public_statuses: UnorderedSet<String>,
+    pub followers: LookupMap<String, UnorderedSet<String>>,
+    pub reactions: LookupMap<String, Vector<OldReaction>>,
+    pub notifications: LookupMap<String, Vector<String>>,
+    pub status_expiry: UnorderedMap<String, u64>,
+    pub analytics: LookupMap<String, u64>,
+}
+
+/// The old per-account collections, kept after `migrate` until `migrate_accounts` has moved
+/// each account over. All four nested collections of an account share the account id as
+/// their prefix.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct LegacyAccounts {
+    pub records: LookupMap<String, String>,
+    pub history: LookupMap<String, Vector<OldStatusRecord>>,
+    pub followers: LookupMap<String, UnorderedSet<String>>,
+    pub reactions: LookupMap<String, Vector<OldReaction>>,
+    pub notifications: LookupMap<String, Vector<String>>,
+}
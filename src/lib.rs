@@ -1,56 +1,207 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::{LookupMap, Vector, UnorderedSet, UnorderedMap};
-use near_sdk::{env, near_bindgen, AccountId};
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, env, near_bindgen, AccountId, Promise};
 use serde::{Deserialize, Serialize};
 
 // Import our new modules
+mod attachments;
+mod config;
 mod history;
+mod legacy;
 mod profile;
 mod reactions;
+mod ring_buffer;
+mod scheduled;
+mod storage;
 mod social;
 mod notifications;
 mod analytics;
+mod mentions;
+mod overview;
+mod tags;
+mod utils;
+mod visibility;
 
-use history::StatusRecord;
+use attachments::{validate_attachments, Attachment};
+use config::Config;
+use history::{
+    parse_status_id, partition_point, AccountStatusPage, NANOS_PER_DAY, DeletedBy, RetentionPolicy, SortOrder, StatusPage, StatusRecord, StatusRevision,
+    Tombstone,
+};
+use legacy::{LegacyAccounts, OldStatusMessage, OldStatusRecord};
+use mentions::extract_mentions;
+use notifications::Notification;
+use overview::{AccountOverview, MAX_BATCH_SIZE};
+use tags::{extract_tags, normalize_tag};
+use utils::{nested_prefix, recover};
+use visibility::{list_key, Visibility};
 use profile::UserProfile;
 use reactions::Reaction;
+use ring_buffer::RingBuffer;
+use scheduled::{ScheduledStatus, MAX_SCHEDULED_PER_ACCOUNT};
+use storage::{storage_cost, StorageAccount, StorageBalance, StorageBalanceBounds, ACCOUNT_STORAGE_BYTES};
 
 near_sdk::setup_alloc!();
 
+const DEFAULT_PAGE_SIZE: u64 = 10;
+const MAX_PAGE_SIZE: u64 = 100;
+const MAX_PRUNED_PER_WRITE: u64 = 50;
+const MAX_SEARCH_SCAN: u64 = 1000;
+const MAX_ACTIVITY_DAYS: u64 = 366;
+// Tags for nested collections, see `nested_prefix`. Uppercase keeps them apart from the
+// lowercase top-level prefixes.
+const HISTORY_TAG: u8 = b'H';
+const REACTIONS_TAG: u8 = b'R';
+const NOTIFICATIONS_TAG: u8 = b'N';
+const FOLLOWERS_TAG: u8 = b'F';
+const FOLLOWING_TAG: u8 = b'G';
+const HOME_FEED_TAG: u8 = b'D';
+const MENTIONS_TAG: u8 = b'M';
+const SCHEDULED_TAG: u8 = b'S';
+const REVISIONS_TAG: u8 = b'V';
+const REPLIES_TAG: u8 = b'P';
+const TAGGED_TAG: u8 = b'T';
+const LIST_TAG: u8 = b'L';
+
+fn page_size(limit: Option<u64>) -> u64 {
+    std::cmp::min(limit.unwrap_or(DEFAULT_PAGE_SIZE), MAX_PAGE_SIZE)
+}
+
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct StatusMessage {
-    records: LookupMap<String, String>,
-    history: LookupMap<String, Vector<StatusRecord>>,
+    history: LookupMap<String, RingBuffer<StatusRecord>>,
     profiles: LookupMap<String, UserProfile>,
     public_statuses: UnorderedSet<String>,
     followers: LookupMap<String, UnorderedSet<String>>,
-    reactions: LookupMap<String, Vector<Reaction>>, // status_id -> reactions
-    notifications: LookupMap<String, Vector<String>>, // account_id -> notifications
+    reactions: LookupMap<String, UnorderedMap<String, Reaction>>, // status_id -> reactor -> reaction
+    notifications: LookupMap<String, RingBuffer<Notification>>, // account_id -> notifications
     status_expiry: UnorderedMap<String, u64>, // account_id -> expiry_timestamp
     analytics: LookupMap<String, u64>, // account_id -> view_count
+    status_counters: LookupMap<String, u64>, // account_id -> next status sequence number
+    revisions: LookupMap<String, Vector<StatusRevision>>, // status_id -> revisions
+    replies: LookupMap<String, UnorderedSet<String>>, // status_id -> reply status_ids
+    mentions: LookupMap<String, UnorderedSet<String>>, // account_id -> status_ids mentioning it
+    current_status: LookupMap<String, String>, // account_id -> status_id of the current status
+    tags: LookupMap<String, UnorderedSet<String>>, // tag -> status_ids
+    expiring: LookupMap<String, Vec<String>>, // account_id -> tagged status_ids that expire
+    scheduled: LookupMap<String, Vector<ScheduledStatus>>, // account_id -> pending scheduled statuses
+    scheduled_accounts: UnorderedSet<String>, // accounts with pending scheduled statuses
+    schedule_counters: LookupMap<String, u64>, // account_id -> next scheduled status id
+    lists: LookupMap<String, UnorderedSet<String>>, // owner_id:list_name -> members
+    pins: LookupMap<String, Vec<String>>, // account_id -> pinned status_ids, first shown first
+    retention: LookupMap<String, RetentionPolicy>, // account_id -> history retention policy
+    tombstones: LookupMap<String, Vec<String>>, // account_id -> deleted status_ids still within the restore window
+    timeline: Vector<String>, // status_ids of every public status, oldest first
+    following: LookupMap<String, UnorderedSet<String>>, // account_id -> accounts it follows
+    home_feeds: LookupMap<String, Vector<String>>, // account_id -> status_ids from followed accounts, oldest first
+    activity: LookupMap<String, u64>, // account_id:day -> statuses posted that day
+    storage_accounts: LookupMap<String, StorageAccount>, // account_id -> storage deposit and bytes used
+    config: Config,
+    owner_id: String,
+    pending_owner_id: Option<String>, // proposed owner until it accepts
+    legacy: Option<LegacyAccounts>, // accounts still in the old layout after `migrate`
+    #[borsh_skip]
+    settled_bytes: u64, // bytes freed this call that were credited to whoever paid for them
 }
 
 impl Default for StatusMessage {
     fn default() -> Self {
         Self {
-            records: LookupMap::new(b"r".to_vec()),
-            history: LookupMap::new(b"h".to_vec()),
+            history: LookupMap::new(b"hi".to_vec()),
             profiles: LookupMap::new(b"p".to_vec()),
             public_statuses: UnorderedSet::new(b"s".to_vec()),
-            followers: LookupMap::new(b"f".to_vec()),
-            reactions: LookupMap::new(b"re".to_vec()),
-            notifications: LookupMap::new(b"n".to_vec()),
+            followers: LookupMap::new(b"fw".to_vec()),
+            reactions: LookupMap::new(b"rc".to_vec()),
+            notifications: LookupMap::new(b"nt".to_vec()),
             status_expiry: UnorderedMap::new(b"e".to_vec()),
             analytics: LookupMap::new(b"a".to_vec()),
+            status_counters: LookupMap::new(b"c".to_vec()),
+            revisions: LookupMap::new(b"v".to_vec()),
+            replies: LookupMap::new(b"rp".to_vec()),
+            mentions: LookupMap::new(b"m".to_vec()),
+            current_status: LookupMap::new(b"cs".to_vec()),
+            tags: LookupMap::new(b"t".to_vec()),
+            expiring: LookupMap::new(b"x".to_vec()),
+            scheduled: LookupMap::new(b"sc".to_vec()),
+            scheduled_accounts: UnorderedSet::new(b"sa".to_vec()),
+            schedule_counters: LookupMap::new(b"scc".to_vec()),
+            lists: LookupMap::new(b"l".to_vec()),
+            pins: LookupMap::new(b"pn".to_vec()),
+            retention: LookupMap::new(b"rt".to_vec()),
+            tombstones: LookupMap::new(b"ts".to_vec()),
+            timeline: Vector::new(b"tl".to_vec()),
+            following: LookupMap::new(b"fg".to_vec()),
+            home_feeds: LookupMap::new(b"hf".to_vec()),
+            activity: LookupMap::new(b"ac".to_vec()),
+            storage_accounts: LookupMap::new(b"sb".to_vec()),
+            config: Config::default(),
+            // Deployments that never called `new` are owned by the contract account itself
+            owner_id: env::current_account_id(),
+            pending_owner_id: None,
+            legacy: None,
+            settled_bytes: 0,
         }
     }
 }
 
 #[near_bindgen]
 impl StatusMessage {
+    #[init]
     #[private]
+    pub fn new(owner_id: String, config: Option<Config>) -> Self {
+        if !env::is_valid_account_id(owner_id.as_bytes()) {
+            env::panic_str("Invalid owner account id");
+        }
+        let config = config.unwrap_or_default();
+        config.validate();
+        Self {
+            config,
+            owner_id,
+            ..Self::default()
+        }
+    }
+    
+    // Ownership
+    
+    pub fn get_owner(&self) -> String {
+        self.owner_id.clone()
+    }
+    
+    pub fn get_pending_owner(&self) -> Option<String> {
+        self.pending_owner_id.clone()
+    }
+    
+    /// Proposes a new owner, who takes over once it calls `accept_owner`. Proposing again
+    /// replaces the pending owner; `None` withdraws the proposal.
+    pub fn propose_owner(&mut self, new_owner_id: Option<String>) {
+        self.ensure_owner();
+        if let Some(new_owner_id) = &new_owner_id {
+            if !env::is_valid_account_id(new_owner_id.as_bytes()) {
+                env::panic_str("Invalid owner account id");
+            }
+        }
+        self.pending_owner_id = new_owner_id;
+    }
+    
+    pub fn accept_owner(&mut self) {
+        let account_id = env::predecessor_account_id();
+        if self.pending_owner_id.as_ref() != Some(&account_id) {
+            env::panic_str("Only the proposed owner can accept ownership");
+        }
+        self.owner_id = account_id;
+        self.pending_owner_id = None;
+    }
+    
+    fn ensure_owner(&self) {
+        if env::predecessor_account_id() != self.owner_id {
+            env::panic_str("Only the owner can call this method");
+        }
+    }
+    
     pub fn cleanup_expired_statuses(&mut self) {
+        self.ensure_owner();
         let mut to_remove = Vec::new();
         
         for (account_id, expiry_time) in self.status_expiry.iter() {
@@ -60,23 +211,33 @@ impl StatusMessage {
         }
         
         for account_id in to_remove {
-            self.records.remove(&account_id);
-            self.public_statuses.remove(&account_id);
-            self.status_expiry.remove(&account_id);
+            self.clear_current_status(&account_id);
+            self.unindex_expired(&account_id);
             
             // Notify user of expiration
-            self.add_notification(&account_id, "Your status has expired".to_string());
+            self.add_notification(&account_id, "Your status has expired".to_string(), None);
         }
     }
 
-    pub fn set_status(&mut self, message: String, is_public: Option<bool>, expires_in_hours: Option<u64>) {
+    #[payable]
+    pub fn set_status(
+        &mut self,
+        message: String,
+        is_public: Option<bool>,
+        expires_in_hours: Option<u64>,
+        attachments: Option<Vec<Attachment>>,
+        visibility: Option<Visibility>,
+    ) -> String {
+        let initial_storage = env::storage_usage();
         // Validate input
-        if message.is_empty() {
-            env::panic_str("Message cannot be empty");
-        }
+        self.validate_message(&message);
+        self.validate_expiry(expires_in_hours);
+        self.validate_visibility(&visibility);
+        let attachments = attachments.unwrap_or_default();
+        validate_attachments(&attachments, self.config.max_attachments);
         
-        let account_id = env::signer_account_id();
-        let public_flag = is_public.unwrap_or(true);
+        let account_id = env::predecessor_account_id();
+        self.publish_due_statuses(&account_id);
         
         // Calculate expiry time if provided
         let expires_at = if let Some(hours) = expires_in_hours {
@@ -89,42 +250,428 @@ impl StatusMessage {
             None
         };
         
-        // Save current status
-        self.records.insert(&account_id, &message);
+        let mut record = StatusRecord::new(&account_id, self.next_status_seq(&account_id), message, expires_at);
+        record.attachments = attachments;
+        record.visibility = Visibility::resolve(visibility, is_public);
+        self.publish_status(&account_id, &record);
+        self.charge_storage(initial_storage);
         
-        // Add to history
-        let mut user_history = self.history.get(&account_id).unwrap_or_else(|| {
-            Vector::new(account_id.as_bytes())
-        });
+        record.id
+    }
+    
+    /// Makes `record` the account's current status and records it in history.
+    fn publish_status(&mut self, account_id: &str, record: &StatusRecord) {
+        let account_id = account_id.to_string();
         
-        user_history.push(&StatusRecord::new(message.clone(), expires_at));
-        self.history.insert(&account_id, &user_history);
+        // Add to history
+        self.append_history(&account_id, record);
+        self.current_status.insert(&account_id, &record.id);
+        self.index_mentions(record);
+        self.index_tags(record);
+        self.record_activity(&account_id, record.timestamp);
         
         // Handle public status
-        if public_flag {
+        if record.visibility == Visibility::Public {
             self.public_statuses.insert(&account_id);
         } else {
             self.public_statuses.remove(&account_id);
         }
         
         // Set expiry if provided
-        if let Some(expiry) = expires_at {
+        if let Some(expiry) = record.expires_at {
             self.status_expiry.insert(&account_id, &expiry);
         } else {
             self.status_expiry.remove(&account_id);
         }
         
-        // Notify followers
-        if public_flag {
-            if let Some(followers) = self.followers.get(&account_id) {
-                for follower in followers.iter() {
-                    self.add_notification(&follower, format!("{} posted a new status", account_id));
+        self.deliver_to_followers(record, format!("{} posted a new status", account_id));
+    }
+
+    fn validate_message(&self, message: &str) {
+        if message.is_empty() {
+            env::panic_str("Message cannot be empty");
+        }
+        if message.len() as u64 > self.config.max_message_bytes {
+            env::panic_str(&format!("Message cannot be longer than {} bytes", self.config.max_message_bytes));
+        }
+    }
+    
+    fn validate_expiry(&self, expires_in_hours: Option<u64>) {
+        if expires_in_hours.map_or(false, |hours| hours > self.config.max_expiry_hours) {
+            env::panic_str(&format!("Statuses cannot expire later than {} hours", self.config.max_expiry_hours));
+        }
+    }
+    
+    fn validate_list_name(&self, name: &str) {
+        if name.is_empty() {
+            env::panic_str("List name cannot be empty");
+        }
+        if name.len() as u64 > self.config.max_name_bytes {
+            env::panic_str(&format!("List name cannot be longer than {} bytes", self.config.max_name_bytes));
+        }
+    }
+    
+    fn validate_visibility(&self, visibility: &Option<Visibility>) {
+        if let Some(Visibility::List(name)) = visibility {
+            self.validate_list_name(name);
+        }
+    }
+
+    /// The account's current status, if it is shown to `viewer_id`. See `Visibility` for why
+    /// this is not access control.
+    pub fn get_status(&self, account_id: String, viewer_id: Option<String>) -> Option<StatusRecord> {
+        self.live_status(&account_id)
+            .filter(|record| self.can_view(record, viewer_id.as_deref()))
+    }
+    
+    /// Current statuses for several accounts at once, in the order given. Entries are `None`
+    /// where `get_status` would return nothing.
+    pub fn get_statuses(&self, account_ids: Vec<String>, viewer_id: Option<String>) -> Vec<Option<StatusRecord>> {
+        if account_ids.len() > MAX_BATCH_SIZE {
+            env::panic_str(&format!("Cannot fetch more than {} accounts at once", MAX_BATCH_SIZE));
+        }
+        account_ids
+            .into_iter()
+            .map(|account_id| self.get_status(account_id, viewer_id.clone()))
+            .collect()
+    }
+    
+    /// Walks an account's history a page at a time. Descending order (the default) starts
+    /// from the newest entry.
+    pub fn get_status_history(
+        &self,
+        account_id: String,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+        order: Option<SortOrder>,
+        viewer_id: Option<String>,
+    ) -> StatusPage {
+        let history = match self.history.get(&account_id) {
+            Some(history) if !history.is_empty() => history,
+            _ => return StatusPage { records: vec![], next_index: None },
+        };
+        let len = history.len();
+        let limit = page_size(limit);
+        
+        let (indexes, next_index): (Vec<u64>, Option<u64>) = match order.unwrap_or(SortOrder::Descending) {
+            SortOrder::Ascending => {
+                let start = from_index.unwrap_or(0);
+                let end = std::cmp::min(start.saturating_add(limit), len);
+                ((start..end).collect(), if end < len { Some(end) } else { None })
+            },
+            SortOrder::Descending => {
+                let start = std::cmp::min(from_index.unwrap_or(len - 1), len - 1);
+                let end = (start + 1).saturating_sub(limit);
+                ((end..=start).rev().collect(), if end > 0 { Some(end - 1) } else { None })
+            },
+        };
+        
+        let records = indexes.into_iter()
+            .map(|i| history.get(i).unwrap())
+            .filter(|record| !record.is_expired() && self.can_view(record, viewer_id.as_deref()))
+            .map(StatusRecord::redacted)
+            .collect();
+        StatusPage { records, next_index }
+    }
+    
+    /// Statuses posted between `from_ts` and `to_ts` (inclusive, in nanoseconds), oldest first.
+    pub fn get_status_history_between(
+        &self,
+        account_id: String,
+        from_ts: u64,
+        to_ts: u64,
+        limit: Option<u64>,
+        viewer_id: Option<String>,
+    ) -> Vec<StatusRecord> {
+        let history = match self.history.get(&account_id) {
+            Some(history) => history,
+            None => return vec![],
+        };
+        
+        let start = partition_point(&history, |record| record.timestamp < from_ts);
+        let end = std::cmp::min(
+            partition_point(&history, |record| record.timestamp <= to_ts),
+            start.saturating_add(page_size(limit)),
+        );
+        (start..end)
+            .map(|i| history.get(i).unwrap())
+            .filter(|record| !record.is_expired() && self.can_view(record, viewer_id.as_deref()))
+            .map(StatusRecord::redacted)
+            .collect()
+    }
+    
+    pub fn get_status_by_id(&self, status_id: String, viewer_id: Option<String>) -> Option<StatusRecord> {
+        let (_, record) = self.find_status(&status_id)?;
+        if record.is_expired() || !self.can_view(&record, viewer_id.as_deref()) {
+            return None;
+        }
+        Some(record.redacted())
+    }
+    
+    #[payable]
+    pub fn edit_status(&mut self, status_id: String, new_message: String) {
+        let initial_storage = env::storage_usage();
+        self.validate_message(&new_message);
+        
+        let account_id = env::predecessor_account_id();
+        let (index, mut record) = match self.find_status(&status_id) {
+            Some((index, record)) if !record.is_deleted() => (index, record),
+            _ => env::panic_str("Status not found"),
+        };
+        if parse_status_id(&status_id).map(|(author, _)| author) != Some(account_id.clone()) {
+            env::panic_str("Only the author can edit a status");
+        }
+        
+        // Keep every version, starting with the original text the first time a status is edited
+        let mut status_revisions = self.revisions.get(&status_id).unwrap_or_else(|| {
+            Vector::new(nested_prefix(REVISIONS_TAG, &status_id))
+        });
+        if status_revisions.is_empty() {
+            status_revisions.push(&StatusRevision {
+                message: record.message.clone(),
+                timestamp: record.timestamp,
+            });
+        }
+        status_revisions.push(&StatusRevision {
+            message: new_message.clone(),
+            timestamp: env::block_timestamp(),
+        });
+        self.revisions.insert(&status_id, &status_revisions);
+        
+        self.unindex_tags(&record);
+        self.unindex_mentions(&record);
+        record.message = new_message.clone();
+        record.edited = true;
+        self.replace_history_entry(index, &record);
+        self.index_tags(&record);
+        self.reindex_mentions(&record);
+        self.charge_storage(initial_storage);
+    }
+    
+    /// Every version of a status, oldest first, if the status is shown to `viewer_id`.
+    /// Deleted statuses have no revisions to show.
+    pub fn get_status_revisions(&self, status_id: String, viewer_id: Option<String>) -> Vec<StatusRevision> {
+        let record = match self.find_status(&status_id) {
+            Some((_, record))
+                if !record.is_expired() && !record.is_deleted() && self.can_view(&record, viewer_id.as_deref()) => record,
+            _ => return vec![],
+        };
+        if let Some(status_revisions) = self.revisions.get(&status_id) {
+            return status_revisions.iter().collect();
+        }
+        vec![StatusRevision {
+            message: record.message,
+            timestamp: record.timestamp,
+        }]
+    }
+    
+    /// Removes the current status. With `purge_history` the whole history goes too,
+    /// along with reactions and index entries pointing at it. Returns the bytes released.
+    /// The caller is credited for its own; reactions and replies go back to their authors.
+    #[payable]
+    pub fn delete_status(&mut self, purge_history: Option<bool>) -> u64 {
+        let initial_storage = env::storage_usage();
+        let account_id = env::predecessor_account_id();
+        self.clear_current_status(&account_id);
+        
+        if purge_history.unwrap_or(false) {
+            // Each entry only touches its own index entries, so this is linear in the history
+            if let Some(mut user_history) = self.history.remove(&account_id) {
+                for record in user_history.iter() {
+                    self.remove_status_data(&record);
                 }
+                user_history.clear();
             }
+            self.tombstones.remove(&account_id);
         }
+        self.charge_storage(initial_storage)
     }
-
-    pub fn get_status(&self, account_id: String) -> Option<String> {
+    
+    /// Deletes a single status, leaving a tombstone in the author's history. The author can
+    /// restore it within the restore window; after that its content is removed for good.
+    /// Returns the bytes released now; the content's bytes are credited once it is removed.
+    #[payable]
+    pub fn delete_status_by_id(&mut self, status_id: String) -> u64 {
+        let initial_storage = env::storage_usage();
+        let account_id = env::predecessor_account_id();
+        if parse_status_id(&status_id).map(|(author, _)| author) != Some(account_id.clone()) {
+            env::panic_str("Only the author can delete a status");
+        }
+        self.soft_delete(&status_id, DeletedBy::Author);
+        self.charge_storage(initial_storage)
+    }
+    
+    /// Deletes a status on behalf of the contract's moderators.
+    pub fn moderate_status(&mut self, status_id: String) {
+        self.ensure_owner();
+        self.soft_delete(&status_id, DeletedBy::Moderator(env::predecessor_account_id()));
+    }
+    
+    #[payable]
+    pub fn restore_status(&mut self, status_id: String) {
+        let initial_storage = env::storage_usage();
+        let account_id = env::predecessor_account_id();
+        if parse_status_id(&status_id).map(|(author, _)| author) != Some(account_id.clone()) {
+            env::panic_str("Only the author can restore a status");
+        }
+        let (index, mut record) = self.find_status(&status_id).unwrap_or_else(|| env::panic_str("Status not found"));
+        let tombstone = match record.deleted.take() {
+            Some(tombstone) if tombstone.deleted_by == DeletedBy::Author
+                && env::block_timestamp() <= tombstone.deleted_at.saturating_add(self.config.restore_window()) => tombstone,
+            Some(_) => env::panic_str("Status can no longer be restored"),
+            None => env::panic_str("Status is not deleted"),
+        };
+        
+        self.replace_history_entry(index, &record);
+        self.index_tags(&record);
+        self.reindex_mentions(&record);
+        
+        // It only becomes current again if nothing has replaced it since
+        if tombstone.was_current && self.current_status.get(&account_id).is_none() && !record.is_expired() {
+            self.current_status.insert(&account_id, &record.id);
+            if record.visibility == Visibility::Public {
+                self.public_statuses.insert(&account_id);
+            }
+            if let Some(expiry) = record.expires_at {
+                self.status_expiry.insert(&account_id, &expiry);
+            }
+        }
+        if let Some(position) = tombstone.pin_position {
+            let mut pinned = self.pins.get(&account_id).unwrap_or_default();
+            // Pins made since the deletion keep their place if the limit has been reached
+            if (pinned.len() as u64) < self.config.max_pinned {
+                let position = (position as usize).min(pinned.len());
+                pinned.insert(position, status_id.clone());
+                self.pins.insert(&account_id, &pinned);
+            }
+        }
+        
+        let mut pending = self.tombstones.get(&account_id).unwrap_or_default();
+        pending.retain(|pending_id| pending_id != &status_id);
+        self.store_tombstones(&account_id, pending);
+        self.charge_storage(initial_storage);
+    }
+    
+    fn soft_delete(&mut self, status_id: &str, deleted_by: DeletedBy) {
+        let (account_id, _) = parse_status_id(status_id).unwrap_or_else(|| env::panic_str("Status not found"));
+        self.finalize_tombstones(&account_id);
+        let (index, mut record) = match self.find_status(status_id) {
+            Some((index, record)) if !record.is_deleted() => (index, record),
+            _ => env::panic_str("Status not found"),
+        };
+        
+        let was_current = self.current_status.get(&account_id).as_deref() == Some(status_id);
+        if was_current {
+            self.clear_current_status(&account_id);
+        }
+        let pin_position = self.pins.get(&account_id)
+            .and_then(|pinned| pinned.iter().position(|pinned_id| pinned_id == status_id))
+            .map(|position| position as u64);
+        // Hide it from indexes now; reactions, replies and revisions stay until the window passes
+        self.unindex_tags(&record);
+        self.unindex_mentions(&record);
+        self.remove_pin(&account_id, status_id);
+        
+        record.deleted = Some(Tombstone {
+            deleted_at: env::block_timestamp(),
+            deleted_by,
+            was_current,
+            pin_position,
+        });
+        self.replace_history_entry(index, &record);
+        
+        let mut pending = self.tombstones.get(&account_id).unwrap_or_default();
+        pending.push(status_id.to_string());
+        self.tombstones.insert(&account_id, &pending);
+    }
+    
+    /// Removes the content and related data of an account's deleted statuses whose restore
+    /// window has passed. Their tombstones stay in history.
+    fn finalize_tombstones(&mut self, account_id: &str) {
+        let account_id = account_id.to_string();
+        let pending = match self.tombstones.get(&account_id) {
+            Some(pending) => pending,
+            None => return,
+        };
+        
+        let now = env::block_timestamp();
+        let mut remaining = Vec::new();
+        for status_id in pending {
+            let (index, mut record) = match self.find_status(&status_id) {
+                Some(found) => found,
+                None => continue, // pruned or purged since
+            };
+            match &record.deleted {
+                Some(tombstone) if now > tombstone.deleted_at.saturating_add(self.config.restore_window()) => {
+                    self.remove_status_data(&record);
+                    record = record.redacted();
+                    record.reply_count = 0;
+                    record.repost_count = 0;
+                    self.replace_history_entry(index, &record);
+                },
+                Some(_) => remaining.push(status_id),
+                None => {},
+            }
+        }
+        self.store_tombstones(&account_id, remaining);
+    }
+    
+    fn store_tombstones(&mut self, account_id: &String, pending: Vec<String>) {
+        if pending.is_empty() {
+            self.tombstones.remove(account_id);
+        } else {
+            self.tombstones.insert(account_id, &pending);
+        }
+    }
+    
+    /// Replaces the contract's limits. Existing data over a new limit is left as it is,
+    /// except history, which is pruned down on the account's next write.
+    pub fn set_config(&mut self, config: Config) {
+        self.ensure_owner();
+        config.validate();
+        self.config = config;
+    }
+    
+    pub fn get_config(&self) -> Config {
+        self.config.clone()
+    }
+    
+    pub fn get_status_count(&self, account_id: String) -> u64 {
+        match self.history.get(&account_id) {
+            Some(history) => history.len(),
+            None => 0,
+        }
+    }
+    
+    /// Statuses posted per day from `from_day` to `to_day` inclusive, one entry per day.
+    /// Days are counted from the Unix epoch in UTC.
+    pub fn get_activity(&self, account_id: String, from_day: u64, to_day: u64) -> Vec<u64> {
+        if to_day < from_day {
+            env::panic_str("to_day cannot be before from_day");
+        }
+        if to_day - from_day >= MAX_ACTIVITY_DAYS {
+            env::panic_str(&format!("Cannot fetch more than {} days of activity at once", MAX_ACTIVITY_DAYS));
+        }
+        (from_day..=to_day)
+            .map(|day| self.activity.get(&format!("{}:{}", account_id, day)).unwrap_or(0))
+            .collect()
+    }
+    
+    fn record_activity(&mut self, account_id: &str, timestamp: u64) {
+        let key = format!("{}:{}", account_id, timestamp / NANOS_PER_DAY);
+        let count = self.activity.get(&key).unwrap_or(0);
+        self.activity.insert(&key, &(count + 1));
+    }
+    
+    fn next_status_seq(&mut self, account_id: &str) -> u64 {
+        let account_id = account_id.to_string();
+        let seq = self.status_counters.get(&account_id).unwrap_or(0);
+        self.status_counters.insert(&account_id, &(seq + 1));
+        seq
+    }
+    
+    fn current_record(&self, account_id: &str) -> Option<StatusRecord> {
+        let account_id = account_id.to_string();
         // Check if status has expired
         if let Some(expiry) = self.status_expiry.get(&account_id) {
             if env::block_timestamp() > expiry {
@@ -132,94 +679,361 @@ impl StatusMessage {
             }
         }
         
-        self.records.get(&account_id)
+        let status_id = self.current_status.get(&account_id)?;
+        self.find_status(&status_id).map(|(_, record)| record)
     }
     
-    pub fn get_status_history(&self, account_id: String) -> Vec<StatusRecord> {
-        match self.history.get(&account_id) {
-            Some(history) => {
-                let len = history.len();
-                let start_index = if len > 10 { len - 10 } else { 0 };
-                (start_index..len)
-                    .map(|i| history.get(i).unwrap())
-                    .filter(|record| !record.is_expired())
-                    .collect()
-            },
+    /// The status an account is showing right now. A scheduled status whose time has come
+    /// is returned even before a write publishes it, under the id it will be published with.
+    fn live_status(&self, account_id: &str) -> Option<StatusRecord> {
+        let now = env::block_timestamp();
+        let due: Vec<ScheduledStatus> = match self.scheduled.get(&account_id.to_string()) {
+            Some(pending) => pending.iter().filter(|status| status.is_due(now)).collect(),
             None => vec![],
+        };
+        
+        if let Some(latest) = due.iter().max_by_key(|status| (status.publish_at, status.id)) {
+            let seq = self.status_counters.get(&account_id.to_string()).unwrap_or(0) + due.len() as u64 - 1;
+            let record = latest.to_record(account_id, seq);
+            if record.is_expired() {
+                return None;
+            }
+            return Some(record);
+        }
+        
+        self.current_record(account_id)
+    }
+    
+    /// Whether `record` is shown to `viewer_id`. Views cannot rely on the caller, so callers
+    /// pass the viewing account explicitly; `None` is an anonymous viewer. Nothing verifies
+    /// `viewer_id`, so this filters what views return but does not keep statuses secret.
+    fn can_view(&self, record: &StatusRecord, viewer_id: Option<&str>) -> bool {
+        let (author, _) = parse_status_id(&record.id).unwrap();
+        if record.visibility == Visibility::Public {
+            return true;
+        }
+        let viewer_id = match viewer_id {
+            Some(viewer_id) => viewer_id.to_string(),
+            None => return false,
+        };
+        if viewer_id == author {
+            return true;
+        }
+        
+        let follows = |follower: &String, followed: &String| {
+            self.followers.get(followed).map_or(false, |followers| followers.contains(follower))
+        };
+        match &record.visibility {
+            Visibility::Public => true,
+            Visibility::FollowersOnly => follows(&viewer_id, &author),
+            Visibility::MutualsOnly => follows(&viewer_id, &author) && follows(&author, &viewer_id),
+            Visibility::Private => false,
+            Visibility::List(name) => self.lists.get(&list_key(&author, name))
+                .map_or(false, |members| members.contains(&viewer_id)),
         }
     }
     
-    pub fn delete_status(&mut self) {
-        let account_id = env::signer_account_id();
-        self.records.remove(&account_id);
+    fn clear_current_status(&mut self, account_id: &str) {
+        let account_id = account_id.to_string();
         self.public_statuses.remove(&account_id);
         self.status_expiry.remove(&account_id);
+        self.remove_current_status(&account_id);
+    }
+    
+    /// Drops the current status pointer and takes the status out of the tag index.
+    fn remove_current_status(&mut self, account_id: &str) {
+        if let Some(status_id) = self.current_status.remove(&account_id.to_string()) {
+            if let Some((_, record)) = self.find_status(&status_id) {
+                self.unindex_tags(&record);
+            }
+        }
+    }
+    
+    fn append_history(&mut self, account_id: &str, record: &StatusRecord) {
+        self.finalize_tombstones(account_id);
+        self.unindex_expired(account_id);
+        let account_id = account_id.to_string();
+        let mut user_history = self.history.get(&account_id).unwrap_or_else(|| {
+            RingBuffer::new(nested_prefix(HISTORY_TAG, &account_id))
+        });
+        
+        user_history.push_back(record);
+        self.history.insert(&account_id, &user_history);
+        if record.visibility == Visibility::Public {
+            self.timeline.push(&record.id);
+        }
+        self.apply_retention(&account_id);
+    }
+    
+    fn replace_history_entry(&mut self, index: u64, record: &StatusRecord) {
+        let (account_id, _) = parse_status_id(&record.id).unwrap();
+        let mut user_history = self.history.get(&account_id).unwrap();
+        user_history.replace(index, record);
+        self.history.insert(&account_id, &user_history);
+    }
+    
+    /// Locates a status in its author's history with a binary search on `seq`.
+    fn find_status(&self, status_id: &str) -> Option<(u64, StatusRecord)> {
+        let (account_id, seq) = parse_status_id(status_id)?;
+        let history = self.history.get(&account_id)?;
         
-        // Optionally keep history but remove current status
+        let index = partition_point(&history, |record| record.seq < seq);
+        history.get(index)
+            .filter(|record| record.seq == seq)
+            .map(|record| (index, record))
     }
     
-    pub fn get_status_count(&self, account_id: String) -> u64 {
-        match self.history.get(&account_id) {
-            Some(history) => history.len(),
-            None => 0,
+    // Retention
+    
+    /// Sets how much history to keep, or keeps everything when `policy` is `None`.
+    #[payable]
+    pub fn set_retention_policy(&mut self, policy: Option<RetentionPolicy>) {
+        let initial_storage = env::storage_usage();
+        let account_id = env::predecessor_account_id();
+        match policy {
+            Some(policy) => {
+                if !policy.is_valid() {
+                    env::panic_str("Retention policy must keep at least one entry or one day");
+                }
+                self.retention.insert(&account_id, &policy);
+                self.apply_retention(&account_id);
+            },
+            None => {
+                self.retention.remove(&account_id);
+            },
+        }
+        self.charge_storage(initial_storage);
+    }
+    
+    pub fn get_retention_policy(&self, account_id: String) -> Option<RetentionPolicy> {
+        self.retention.get(&account_id)
+    }
+    
+    /// Prunes the oldest history entries that fall outside the account's retention policy or
+    /// past the configured maximum number of entries. The current status is never pruned but
+    /// still counts towards what is kept. Each write prunes a bounded number of entries.
+    fn apply_retention(&mut self, account_id: &str) {
+        let account_id = account_id.to_string();
+        let policy = self.retention.get(&account_id);
+        let max_entries = self.config.max_history_entries;
+        let now = env::block_timestamp();
+        let current_id = self.current_status.get(&account_id);
+        
+        for _ in 0..MAX_PRUNED_PER_WRITE {
+            let user_history = match self.history.get(&account_id) {
+                Some(user_history) => user_history,
+                None => return,
+            };
+            let len = user_history.len();
+            // When the current status is the oldest entry, prune the one behind it instead
+            let index = match user_history.get(0) {
+                Some(record) if current_id.as_ref() == Some(&record.id) => 1,
+                Some(_) => 0,
+                None => return,
+            };
+            let record = match user_history.get(index) {
+                Some(record) => record,
+                None => return,
+            };
+            let prunes = len > max_entries
+                || policy.as_ref().map_or(false, |policy| policy.prunes(&record, len, now));
+            if !prunes {
+                return;
+            }
+            
+            // Removing a status can update its parent in history, so history is read again
+            self.remove_status_data(&record);
+            let mut user_history = self.history.get(&account_id).unwrap();
+            let oldest = user_history.pop_front().unwrap();
+            if index == 1 {
+                user_history.replace(0, &oldest);
+            }
+            self.history.insert(&account_id, &user_history);
+        }
+    }
+    
+    /// Removes everything that points at a status which is leaving history.
+    fn remove_status_data(&mut self, record: &StatusRecord) {
+        let (author, _) = parse_status_id(&record.id).unwrap();
+        self.unindex_tags(record);
+        self.unindex_mentions(record);
+        self.remove_pin(&author, &record.id);
+        if let Some(mut status_revisions) = self.revisions.remove(&record.id) {
+            status_revisions.clear();
+        }
+        self.clear_replies(&record.id);
+        
+        if let Some(parent_id) = &record.reply_to {
+            if let Some((index, mut parent)) = self.find_status(parent_id) {
+                parent.reply_count = parent.reply_count.saturating_sub(1);
+                self.replace_history_entry(index, &parent);
+            }
+            if let Some(mut status_replies) = self.replies.get(parent_id) {
+                status_replies.remove(&record.id);
+                self.replies.insert(parent_id, &status_replies);
+            }
+        }
+        if let Some(original_id) = &record.repost_of {
+            if let Some((index, mut original)) = self.find_status(original_id) {
+                original.repost_count = original.repost_count.saturating_sub(1);
+                self.replace_history_entry(index, &original);
+            }
+        }
+        
+        self.clear_reactions(&record.id);
+    }
+    
+    /// Removes the index of replies to a status. Each entry goes back to the replier who
+    /// paid for it.
+    fn clear_replies(&mut self, status_id: &String) {
+        let initial_storage = env::storage_usage();
+        let initial_settled = self.settled_bytes;
+        if let Some(mut status_replies) = self.replies.remove(status_id) {
+            for reply_id in status_replies.to_vec() {
+                let entry_storage = env::storage_usage();
+                status_replies.remove(&reply_id);
+                let (replier, _) = parse_status_id(&reply_id).unwrap();
+                self.credit_storage(Some(&replier), entry_storage);
+            }
+        }
+        self.settle_storage(initial_storage, initial_settled);
+    }
+    
+    /// Removes a status's reactions. Each goes back to the account that reacted.
+    fn clear_reactions(&mut self, status_id: &String) {
+        let initial_storage = env::storage_usage();
+        let initial_settled = self.settled_bytes;
+        if let Some(mut status_reactions) = self.reactions.remove(status_id) {
+            for reactor_id in status_reactions.keys().collect::<Vec<_>>() {
+                let entry_storage = env::storage_usage();
+                status_reactions.remove(&reactor_id);
+                self.credit_storage(Some(&reactor_id), entry_storage);
+            }
         }
+        self.settle_storage(initial_storage, initial_settled);
     }
     
     // Previous features
     
+    #[payable]
     pub fn set_profile(&mut self, name: String, bio: String) {
-        let account_id = env::signer_account_id();
+        let initial_storage = env::storage_usage();
+        if name.len() as u64 > self.config.max_name_bytes {
+            env::panic_str(&format!("Name cannot be longer than {} bytes", self.config.max_name_bytes));
+        }
+        if bio.len() as u64 > self.config.max_bio_bytes {
+            env::panic_str(&format!("Bio cannot be longer than {} bytes", self.config.max_bio_bytes));
+        }
+        let account_id = env::predecessor_account_id();
         let profile = UserProfile {
             name,
             bio,
             is_public: true,
         };
         self.profiles.insert(&account_id, &profile);
+        self.charge_storage(initial_storage);
     }
     
     pub fn get_profile(&self, account_id: String) -> Option<UserProfile> {
         self.profiles.get(&account_id)
     }
     
-    pub fn get_public_statuses(&self) -> Vec<(String, String)> {
-        let mut result = Vec::new();
-        for account_id in self.public_statuses.iter() {
-            // Check if status has expired
-            if let Some(expiry) = self.status_expiry.get(&account_id) {
-                if env::block_timestamp() > expiry {
-                    continue; // Skip expired statuses
-                }
-            }
-            
-            if let Some(status) = self.records.get(&account_id) {
-                result.push((account_id.clone(), status));
+    /// Current public statuses, a page of accounts at a time. Expired and non-public statuses
+    /// are skipped, so a page may come back short.
+    pub fn get_public_statuses(&self, from_index: Option<u64>, limit: Option<u64>) -> AccountStatusPage {
+        let total = self.public_account_count();
+        let start = from_index.unwrap_or(0);
+        let end = std::cmp::min(start.saturating_add(page_size(limit)), total);
+        
+        let statuses = (start..end)
+            .filter_map(|i| self.public_status_at(i))
+            .collect();
+        AccountStatusPage {
+            statuses,
+            total,
+            next_index: if end < total { Some(end) } else { None },
+        }
+    }
+    
+    /// Accounts that may currently show a public status are indexed as `public_statuses`
+    /// followed by accounts with pending scheduled statuses, which may already be due.
+    /// The order is stable as long as no account leaves either set.
+    fn public_account_count(&self) -> u64 {
+        self.public_statuses.len() + self.scheduled_accounts.len()
+    }
+    
+    fn public_status_at(&self, index: u64) -> Option<(String, StatusRecord)> {
+        let published = self.public_statuses.as_vector();
+        let account_id = if index < published.len() {
+            published.get(index)?
+        } else {
+            let account_id = self.scheduled_accounts.as_vector().get(index - published.len())?;
+            if self.public_statuses.contains(&account_id) {
+                return None; // already listed above
             }
+            account_id
+        };
+        
+        self.live_status(&account_id)
+            .filter(|status| status.visibility == Visibility::Public)
+            .map(|status| (account_id, status))
+    }
+    
+    /// Every public status, newest first. Pass `next_index` back as `before_cursor` for the
+    /// next page. Expired and deleted statuses are skipped, so a page may come back short.
+    pub fn get_timeline(&self, before_cursor: Option<u64>, limit: Option<u64>) -> StatusPage {
+        let end = std::cmp::min(before_cursor.unwrap_or(u64::MAX), self.timeline.len());
+        let start = end.saturating_sub(page_size(limit));
+        
+        let records = (start..end).rev()
+            .filter_map(|i| self.find_status(&self.timeline.get(i).unwrap()))
+            .map(|(_, record)| record)
+            .filter(|record| !record.is_expired() && !record.is_deleted())
+            .collect();
+        StatusPage {
+            records,
+            next_index: if start > 0 { Some(start) } else { None },
         }
-        result
     }
     
+    #[payable]
     pub fn follow(&mut self, account_id: String) {
-        let follower_id = env::signer_account_id();
+        let initial_storage = env::storage_usage();
+        let follower_id = env::predecessor_account_id();
         if follower_id == account_id {
             env::panic_str("Cannot follow yourself");
         }
         
         let mut user_followers = self.followers.get(&account_id).unwrap_or_else(|| {
-            UnorderedSet::new(account_id.as_bytes())
+            UnorderedSet::new(nested_prefix(FOLLOWERS_TAG, &account_id))
         });
         
         user_followers.insert(&follower_id);
         self.followers.insert(&account_id, &user_followers);
         
-        self.add_notification(&account_id, format!("{} started following you", follower_id));
+        let mut user_following = self.following.get(&follower_id).unwrap_or_else(|| {
+            UnorderedSet::new(nested_prefix(FOLLOWING_TAG, &follower_id))
+        });
+        user_following.insert(&account_id);
+        self.following.insert(&follower_id, &user_following);
+        
+        self.add_notification(&account_id, format!("{} started following you", follower_id), Some(&follower_id));
+        self.charge_storage(initial_storage);
     }
     
+    #[payable]
     pub fn unfollow(&mut self, account_id: String) {
-        let follower_id = env::signer_account_id();
+        let initial_storage = env::storage_usage();
+        let follower_id = env::predecessor_account_id();
         if let Some(mut user_followers) = self.followers.get(&account_id) {
             user_followers.remove(&follower_id);
             self.followers.insert(&account_id, &user_followers);
         }
+        if let Some(mut user_following) = self.following.get(&follower_id) {
+            user_following.remove(&account_id);
+            self.following.insert(&follower_id, &user_following);
+        }
+        self.charge_storage(initial_storage);
     }
     
     pub fn get_followers(&self, account_id: String) -> Vec<String> {
@@ -231,87 +1045,115 @@ impl StatusMessage {
     }
     
     pub fn get_following(&self) -> Vec<String> {
-        let account_id = env::signer_account_id();
-        let mut result = Vec::new();
-        
-        for (user, followers) in self.followers.iter() {
-            if followers.contains(&account_id) {
-                result.push(user);
-            }
+        let account_id = env::predecessor_account_id();
+        if let Some(following) = self.following.get(&account_id) {
+            following.iter().collect()
+        } else {
+            vec![]
         }
+    }
+    
+    /// Statuses from the accounts `account_id` follows, newest first. Statuses are added to
+    /// the feed when posted, so a page costs the same however many accounts are followed.
+    /// Pass `next_index` back as `cursor` for the next page.
+    pub fn get_home_feed(&self, account_id: String, cursor: Option<u64>, limit: Option<u64>) -> StatusPage {
+        let feed = match self.home_feeds.get(&account_id) {
+            Some(feed) => feed,
+            None => return StatusPage { records: vec![], next_index: None },
+        };
+        let following = self.following.get(&account_id);
+        let end = std::cmp::min(cursor.unwrap_or(u64::MAX), feed.len());
+        let start = end.saturating_sub(page_size(limit));
         
-        result
+        let records = (start..end).rev()
+            .filter_map(|i| self.find_status(&feed.get(i).unwrap()))
+            .map(|(_, record)| record)
+            .filter(|record| {
+                let (author, _) = parse_status_id(&record.id).unwrap();
+                following.as_ref().map_or(false, |following| following.contains(&author))
+                    && !record.is_expired()
+                    && !record.is_deleted()
+                    && self.can_view(record, Some(&account_id))
+            })
+            .collect();
+        StatusPage {
+            records,
+            next_index: if start > 0 { Some(start) } else { None },
+        }
     }
     
-    pub fn search_status(&self, query: String) -> Vec<(String, String)> {
-        let mut result = Vec::new();
+    /// Searches current public statuses in the same order as `get_public_statuses`. Collects
+    /// up to `limit` matches, scanning a bounded number of accounts per call.
+    pub fn search_status(&self, query: String, from_index: Option<u64>, limit: Option<u64>) -> AccountStatusPage {
         let lowercase_query = query.to_lowercase();
+        let total = self.public_account_count();
+        let limit = page_size(limit);
+        let mut statuses = Vec::new();
         
-        for account_id in self.public_statuses.iter() {
-            // Check if status has expired
-            if let Some(expiry) = self.status_expiry.get(&account_id) {
-                if env::block_timestamp() > expiry {
-                    continue; // Skip expired statuses
-                }
-            }
-            
-            if let Some(status) = self.records.get(&account_id) {
-                if status.to_lowercase().contains(&lowercase_query) {
-                    result.push((account_id.clone(), status));
+        let start = from_index.unwrap_or(0);
+        let scan_end = std::cmp::min(start.saturating_add(MAX_SEARCH_SCAN), total);
+        let mut index = start;
+        while index < scan_end && (statuses.len() as u64) < limit {
+            if let Some((account_id, status)) = self.public_status_at(index) {
+                if status.message.to_lowercase().contains(&lowercase_query) {
+                    statuses.push((account_id, status));
                 }
             }
+            index += 1;
         }
         
-        result
+        AccountStatusPage {
+            statuses,
+            total,
+            next_index: if index < total { Some(index) } else { None },
+        }
     }
     
     // New features: Reactions
     
+    #[payable]
     pub fn add_reaction(&mut self, account_id: String, reaction_type: String) {
-        let reactor_id = env::signer_account_id();
-        let reaction = Reaction {
-            account_id: reactor_id,
-            reaction_type,
-            timestamp: env::block_timestamp(),
-        };
+        let initial_storage = env::storage_usage();
+        if reaction_type.is_empty() || reaction_type.len() as u64 > self.config.max_reaction_bytes {
+            env::panic_str(&format!("Reaction type must be between 1 and {} bytes", self.config.max_reaction_bytes));
+        }
+        let reactor_id = env::predecessor_account_id();
+        let status_id = self.current_status.get(&account_id)
+            .unwrap_or_else(|| env::panic_str("Status not found"));
         
-        let mut status_reactions = self.reactions.get(&account_id).unwrap_or_else(|| {
-            Vector::new(account_id.as_bytes())
+        let mut status_reactions = self.reactions.get(&status_id).unwrap_or_else(|| {
+            UnorderedMap::new(nested_prefix(REACTIONS_TAG, &status_id))
         });
         
-        // Check if user already reacted
-        let mut already_reacted = false;
-        for i in 0..status_reactions.len() {
-            if let Some(existing_reaction) = status_reactions.get(i) {
-                if existing_reaction.account_id == reactor_id {
-                    already_reacted = true;
-                    break;
-                }
-            }
-        }
-        
-        if !already_reacted {
-            status_reactions.push(&reaction);
-            self.reactions.insert(&account_id, &status_reactions);
+        // Each account reacts to a status once
+        if status_reactions.get(&reactor_id).is_none() {
+            status_reactions.insert(&reactor_id, &Reaction {
+                account_id: reactor_id.clone(),
+                reaction_type,
+                timestamp: env::block_timestamp(),
+                status_id: Some(status_id.clone()),
+            });
+            self.reactions.insert(&status_id, &status_reactions);
             
             // Notify user
-            self.add_notification(&account_id, format!("{} reacted to your status", reactor_id));
+            self.add_notification(&account_id, format!("{} reacted to your status", reactor_id), Some(&reactor_id));
         }
+        self.charge_storage(initial_storage);
     }
     
+    /// Reactions to the account's current status.
     pub fn get_reactions(&self, account_id: String) -> Vec<Reaction> {
-        if let Some(reactions) = self.reactions.get(&account_id) {
-            reactions.iter().collect()
-        } else {
-            vec![]
+        match self.current_reactions(&account_id) {
+            Some(reactions) => reactions.values().collect(),
+            None => vec![],
         }
     }
     
     pub fn get_reaction_counts(&self, account_id: String) -> std::collections::HashMap<String, u64> {
         let mut counts = std::collections::HashMap::new();
         
-        if let Some(reactions) = self.reactions.get(&account_id) {
-            for reaction in reactions.iter() {
+        if let Some(reactions) = self.current_reactions(&account_id) {
+            for reaction in reactions.values() {
                 *counts.entry(reaction.reaction_type.clone()).or_insert(0) += 1;
             }
         }
@@ -319,51 +1161,586 @@ impl StatusMessage {
         counts
     }
     
+    fn current_reactions(&self, account_id: &str) -> Option<UnorderedMap<String, Reaction>> {
+        let status_id = self.current_status.get(&account_id.to_string())?;
+        self.reactions.get(&status_id)
+    }
+    
+    // Replies
+    
+    #[payable]
+    pub fn reply_to(&mut self, status_id: String, message: String) -> String {
+        let initial_storage = env::storage_usage();
+        self.validate_message(&message);
+        
+        let account_id = env::predecessor_account_id();
+        self.publish_due_statuses(&account_id);
+        let (parent_index, mut parent) = match self.find_status(&status_id) {
+            Some((index, record)) if !record.is_expired() && !record.is_deleted() && self.can_view(&record, Some(&account_id)) => {
+                (index, record)
+            },
+            _ => env::panic_str("Status not found"),
+        };
+        
+        let mut reply = StatusRecord::new(&account_id, self.next_status_seq(&account_id), message, None);
+        reply.reply_to = Some(status_id.clone());
+        
+        // Update the parent first: appending the reply may prune and shift history
+        parent.reply_count += 1;
+        self.replace_history_entry(parent_index, &parent);
+        
+        let mut status_replies = self.replies.get(&status_id).unwrap_or_else(|| {
+            UnorderedSet::new(nested_prefix(REPLIES_TAG, &status_id))
+        });
+        status_replies.insert(&reply.id);
+        self.replies.insert(&status_id, &status_replies);
+        
+        // Replies live in the replier's history but do not replace their current status
+        self.append_history(&account_id, &reply);
+        self.index_mentions(&reply);
+        self.index_tags(&reply);
+        
+        let (parent_author, _) = parse_status_id(&status_id).unwrap();
+        if parent_author != account_id {
+            self.add_notification(&parent_author, format!("{} replied to your status", account_id), Some(&account_id));
+        }
+        self.charge_storage(initial_storage);
+        
+        reply.id
+    }
+    
+    pub fn get_replies(&self, status_id: String, from: u64, limit: u64, viewer_id: Option<String>) -> Vec<StatusRecord> {
+        match self.replies.get(&status_id) {
+            Some(status_replies) => {
+                let status_replies = status_replies.as_vector();
+                let end = std::cmp::min(from.saturating_add(page_size(Some(limit))), status_replies.len());
+                (from..end)
+                    .filter_map(|i| self.find_status(&status_replies.get(i).unwrap()))
+                    .map(|(_, record)| record)
+                    .filter(|record| !record.is_expired() && self.can_view(record, viewer_id.as_deref()))
+                    .map(StatusRecord::redacted)
+                    .collect()
+            },
+            None => vec![],
+        }
+    }
+    
+    // Scheduled statuses
+    
+    #[payable]
+    pub fn schedule_status(
+        &mut self,
+        message: String,
+        publish_at: u64,
+        is_public: Option<bool>,
+        expires_in_hours: Option<u64>,
+        attachments: Option<Vec<Attachment>>,
+        visibility: Option<Visibility>,
+    ) -> u64 {
+        let initial_storage = env::storage_usage();
+        self.validate_message(&message);
+        self.validate_expiry(expires_in_hours);
+        self.validate_visibility(&visibility);
+        if publish_at <= env::block_timestamp() {
+            env::panic_str("publish_at must be in the future");
+        }
+        let attachments = attachments.unwrap_or_default();
+        validate_attachments(&attachments, self.config.max_attachments);
+        
+        let account_id = env::predecessor_account_id();
+        self.publish_due_statuses(&account_id);
+        
+        let mut pending = self.scheduled.get(&account_id).unwrap_or_else(|| {
+            Vector::new(nested_prefix(SCHEDULED_TAG, &account_id))
+        });
+        if pending.len() >= MAX_SCHEDULED_PER_ACCOUNT {
+            env::panic_str("Too many scheduled statuses");
+        }
+        
+        let id = self.schedule_counters.get(&account_id).unwrap_or(0);
+        self.schedule_counters.insert(&account_id, &(id + 1));
+        pending.push(&ScheduledStatus {
+            id,
+            message,
+            publish_at,
+            visibility: Visibility::resolve(visibility, is_public),
+            expires_in_hours,
+            attachments,
+        });
+        self.scheduled.insert(&account_id, &pending);
+        self.scheduled_accounts.insert(&account_id);
+        self.charge_storage(initial_storage);
+        
+        id
+    }
+    
+    /// Pending scheduled statuses, shown only when `viewer_id` is their author.
+    pub fn get_scheduled_statuses(&self, account_id: String, viewer_id: Option<String>) -> Vec<ScheduledStatus> {
+        if viewer_id.as_ref() != Some(&account_id) {
+            return vec![];
+        }
+        let now = env::block_timestamp();
+        match self.scheduled.get(&account_id) {
+            Some(pending) => pending.iter().filter(|status| !status.is_due(now)).collect(),
+            None => vec![],
+        }
+    }
+    
+    #[payable]
+    pub fn cancel_scheduled_status(&mut self, schedule_id: u64) {
+        let initial_storage = env::storage_usage();
+        let account_id = env::predecessor_account_id();
+        self.publish_due_statuses(&account_id);
+        
+        let mut pending = self.scheduled.get(&account_id).unwrap_or_else(|| env::panic_str("Scheduled status not found"));
+        let index = (0..pending.len())
+            .find(|&i| pending.get(i).unwrap().id == schedule_id)
+            .unwrap_or_else(|| env::panic_str("Scheduled status not found"));
+        pending.swap_remove(index);
+        self.store_scheduled(&account_id, pending);
+        self.charge_storage(initial_storage);
+    }
+    
+    /// Publishes an account's due scheduled statuses. Anyone may call this; the author's own
+    /// writes do it automatically. The author pays for the storage either way, and any
+    /// attached deposit goes to the author's storage balance.
+    #[payable]
+    pub fn publish_scheduled(&mut self, account_id: String) {
+        let initial_storage = env::storage_usage();
+        self.publish_due_statuses(&account_id);
+        self.charge_account(&account_id, initial_storage);
+    }
+    
+    fn publish_due_statuses(&mut self, account_id: &str) {
+        let account_id = account_id.to_string();
+        let mut pending = match self.scheduled.get(&account_id) {
+            Some(pending) => pending,
+            None => return,
+        };
+        
+        let now = env::block_timestamp();
+        let mut due = Vec::new();
+        let mut i = 0;
+        while i < pending.len() {
+            if pending.get(i).unwrap().is_due(now) {
+                due.push(pending.swap_remove(i));
+            } else {
+                i += 1;
+            }
+        }
+        if due.is_empty() {
+            return;
+        }
+        
+        due.sort_by_key(|status| (status.publish_at, status.id));
+        for status in due {
+            let record = status.to_record(&account_id, self.next_status_seq(&account_id));
+            self.publish_status(&account_id, &record);
+        }
+        self.store_scheduled(&account_id, pending);
+    }
+    
+    fn store_scheduled(&mut self, account_id: &String, pending: Vector<ScheduledStatus>) {
+        if pending.is_empty() {
+            self.scheduled.remove(account_id);
+            self.scheduled_accounts.remove(account_id);
+        } else {
+            self.scheduled.insert(account_id, &pending);
+        }
+    }
+    
+    // Pins
+    
+    #[payable]
+    pub fn pin_status(&mut self, status_id: String) {
+        let initial_storage = env::storage_usage();
+        let account_id = env::predecessor_account_id();
+        match parse_status_id(&status_id) {
+            Some((author, _)) if author == account_id => {},
+            _ => env::panic_str("Only your own statuses can be pinned"),
+        }
+        match self.find_status(&status_id) {
+            Some((_, record)) if !record.is_deleted() => {},
+            _ => env::panic_str("Status not found"),
+        }
+        
+        let mut pinned = self.pins.get(&account_id).unwrap_or_default();
+        if !pinned.contains(&status_id) {
+            if pinned.len() as u64 >= self.config.max_pinned {
+                env::panic_str(&format!("Cannot pin more than {} statuses", self.config.max_pinned));
+            }
+            // The most recently pinned status is shown first
+            pinned.insert(0, status_id);
+            self.pins.insert(&account_id, &pinned);
+        }
+        self.charge_storage(initial_storage);
+    }
+    
+    #[payable]
+    pub fn unpin_status(&mut self, status_id: String) {
+        let initial_storage = env::storage_usage();
+        let account_id = env::predecessor_account_id();
+        self.remove_pin(&account_id, &status_id);
+        self.charge_storage(initial_storage);
+    }
+    
+    pub fn get_pinned(&self, account_id: String, viewer_id: Option<String>) -> Vec<StatusRecord> {
+        self.pins.get(&account_id)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|status_id| self.find_status(status_id))
+            .map(|(_, record)| record)
+            .filter(|record| !record.is_expired() && !record.is_deleted() && self.can_view(record, viewer_id.as_deref()))
+            .collect()
+    }
+    
+    fn remove_pin(&mut self, account_id: &str, status_id: &str) {
+        let account_id = account_id.to_string();
+        if let Some(mut pinned) = self.pins.get(&account_id) {
+            pinned.retain(|pinned_id| pinned_id != status_id);
+            if pinned.is_empty() {
+                self.pins.remove(&account_id);
+            } else {
+                self.pins.insert(&account_id, &pinned);
+            }
+        }
+    }
+    
+    // Custom lists
+    
+    #[payable]
+    pub fn add_to_list(&mut self, name: String, account_id: String) {
+        let initial_storage = env::storage_usage();
+        self.validate_list_name(&name);
+        let key = list_key(&env::predecessor_account_id(), &name);
+        let mut members = self.lists.get(&key).unwrap_or_else(|| {
+            UnorderedSet::new(nested_prefix(LIST_TAG, &key))
+        });
+        members.insert(&account_id);
+        self.lists.insert(&key, &members);
+        self.charge_storage(initial_storage);
+    }
+    
+    #[payable]
+    pub fn remove_from_list(&mut self, name: String, account_id: String) {
+        let initial_storage = env::storage_usage();
+        let key = list_key(&env::predecessor_account_id(), &name);
+        if let Some(mut members) = self.lists.get(&key) {
+            members.remove(&account_id);
+            self.lists.insert(&key, &members);
+        }
+        self.charge_storage(initial_storage);
+    }
+    
+    pub fn get_list(&self, owner_id: String, name: String) -> Vec<String> {
+        match self.lists.get(&list_key(&owner_id, &name)) {
+            Some(members) => members.iter().collect(),
+            None => vec![],
+        }
+    }
+    
+    // Reposts
+    
+    /// Re-shares a status. Without a comment this is a plain repost, with one it is a quote.
+    #[payable]
+    pub fn repost(&mut self, status_id: String, comment: Option<String>) -> String {
+        let initial_storage = env::storage_usage();
+        let account_id = env::predecessor_account_id();
+        self.publish_due_statuses(&account_id);
+        let target = match self.find_status(&status_id) {
+            Some((_, record)) if !record.is_expired() && !record.is_deleted() && self.can_view(&record, Some(&account_id)) => {
+                record
+            },
+            _ => env::panic_str("Status not found"),
+        };
+        
+        // Reposting a plain repost credits the original status instead
+        let original_id = match &target.repost_of {
+            Some(original_id) if target.message.is_empty() => original_id.clone(),
+            _ => status_id,
+        };
+        let (original_index, mut original) = self.find_status(&original_id).unwrap_or_else(|| env::panic_str("Status not found"));
+        let (original_author, _) = parse_status_id(&original_id).unwrap();
+        
+        let message = comment.unwrap_or_default();
+        if message.len() as u64 > self.config.max_message_bytes {
+            env::panic_str(&format!("Message cannot be longer than {} bytes", self.config.max_message_bytes));
+        }
+        let is_quote = !message.is_empty();
+        let mut record = StatusRecord::new(&account_id, self.next_status_seq(&account_id), message, None);
+        record.repost_of = Some(original_id);
+        
+        // Update the original first: appending the repost may prune and shift history
+        original.repost_count += 1;
+        self.replace_history_entry(original_index, &original);
+        
+        self.append_history(&account_id, &record);
+        self.index_mentions(&record);
+        self.index_tags(&record);
+        
+        let action = if is_quote { "quoted" } else { "reposted" };
+        if original_author != account_id {
+            self.add_notification(&original_author, format!("{} {} your status", account_id, action), Some(&account_id));
+        }
+        self.deliver_to_followers(&record, format!("{} {} a status by {}", account_id, action, original_author));
+        self.charge_storage(initial_storage);
+        
+        record.id
+    }
+    
+    // Mentions
+    
+    fn index_mentions(&mut self, record: &StatusRecord) {
+        let (author, _) = parse_status_id(&record.id).unwrap();
+        for mentioned in self.reindex_mentions(record) {
+            self.add_notification(&mentioned, format!("{} mentioned you in a status", author), Some(&author));
+        }
+    }
+    
+    /// Adds a status to the mention index of every account it mentions, returning them.
+    fn reindex_mentions(&mut self, record: &StatusRecord) -> Vec<String> {
+        let (author, _) = parse_status_id(&record.id).unwrap();
+        let mut mentioned_accounts = Vec::new();
+        for mentioned in extract_mentions(&record.message) {
+            if mentioned == author {
+                continue;
+            }
+            
+            let mut account_mentions = self.mentions.get(&mentioned).unwrap_or_else(|| {
+                UnorderedSet::new(nested_prefix(MENTIONS_TAG, &mentioned))
+            });
+            account_mentions.insert(&record.id);
+            self.mentions.insert(&mentioned, &account_mentions);
+            mentioned_accounts.push(mentioned);
+        }
+        mentioned_accounts
+    }
+    
+    fn unindex_mentions(&mut self, record: &StatusRecord) {
+        for mentioned in extract_mentions(&record.message) {
+            if let Some(mut account_mentions) = self.mentions.get(&mentioned) {
+                account_mentions.remove(&record.id);
+                if account_mentions.is_empty() {
+                    self.mentions.remove(&mentioned);
+                } else {
+                    self.mentions.insert(&mentioned, &account_mentions);
+                }
+            }
+        }
+    }
+    
+    pub fn get_mentions(&self, account_id: String, from: u64, limit: u64, viewer_id: Option<String>) -> Vec<StatusRecord> {
+        match self.mentions.get(&account_id) {
+            Some(account_mentions) => {
+                let account_mentions = account_mentions.as_vector();
+                let end = std::cmp::min(from.saturating_add(page_size(Some(limit))), account_mentions.len());
+                (from..end)
+                    .filter_map(|i| self.find_status(&account_mentions.get(i).unwrap()))
+                    .map(|(_, record)| record)
+                    .filter(|record| !record.is_expired() && self.can_view(record, viewer_id.as_deref()))
+                    .collect()
+            },
+            None => vec![],
+        }
+    }
+    
+    // Hashtags
+    
+    fn index_tags(&mut self, record: &StatusRecord) {
+        let tags = extract_tags(&record.message);
+        if tags.is_empty() {
+            return;
+        }
+        for tag in tags {
+            let mut tagged = self.tags.get(&tag).unwrap_or_else(|| {
+                UnorderedSet::new(nested_prefix(TAGGED_TAG, &tag))
+            });
+            tagged.insert(&record.id);
+            self.tags.insert(&tag, &tagged);
+        }
+        
+        // Remember statuses that expire so `unindex_expired` can take them out later
+        if record.expires_at.is_some() {
+            let (author, _) = parse_status_id(&record.id).unwrap();
+            let mut expiring = self.expiring.get(&author).unwrap_or_default();
+            if !expiring.contains(&record.id) {
+                expiring.push(record.id.clone());
+                self.expiring.insert(&author, &expiring);
+            }
+        }
+    }
+    
+    /// Takes the account's expired statuses out of the tag index. Runs whenever the account
+    /// posts and when expired statuses are cleaned up.
+    fn unindex_expired(&mut self, account_id: &str) {
+        let account_id = account_id.to_string();
+        let expiring = match self.expiring.get(&account_id) {
+            Some(expiring) => expiring,
+            None => return,
+        };
+        
+        let mut remaining = Vec::new();
+        for status_id in expiring {
+            match self.find_status(&status_id) {
+                Some((_, record)) if record.is_expired() => self.unindex_tags(&record),
+                Some(_) => remaining.push(status_id),
+                None => {}, // pruned or purged since
+            }
+        }
+        if remaining.is_empty() {
+            self.expiring.remove(&account_id);
+        } else {
+            self.expiring.insert(&account_id, &remaining);
+        }
+    }
+    
+    fn unindex_tags(&mut self, record: &StatusRecord) {
+        for tag in extract_tags(&record.message) {
+            if let Some(mut tagged) = self.tags.get(&tag) {
+                tagged.remove(&record.id);
+                if tagged.is_empty() {
+                    self.tags.remove(&tag);
+                } else {
+                    self.tags.insert(&tag, &tagged);
+                }
+            }
+        }
+    }
+    
+    pub fn get_statuses_by_tag(&self, tag: String, from: u64, limit: u64, viewer_id: Option<String>) -> Vec<StatusRecord> {
+        match self.tags.get(&normalize_tag(&tag)) {
+            Some(tagged) => {
+                let status_ids = tagged.as_vector();
+                let end = std::cmp::min(from.saturating_add(page_size(Some(limit))), status_ids.len());
+                (from..end)
+                    .filter_map(|i| self.find_status(&status_ids.get(i).unwrap()))
+                    .map(|(_, record)| record)
+                    .filter(|record| !record.is_expired() && self.can_view(record, viewer_id.as_deref()))
+                    .collect()
+            },
+            None => vec![],
+        }
+    }
+    
+    /// Number of statuses indexed under `tag`. Expired statuses leave the index the next time
+    /// their author posts or expired statuses are cleaned up; until then they are counted
+    /// here but skipped by `get_statuses_by_tag`.
+    pub fn get_tag_count(&self, tag: String) -> u64 {
+        match self.tags.get(&normalize_tag(&tag)) {
+            Some(tagged) => tagged.len(),
+            None => 0,
+        }
+    }
+    
     // Notifications
     
-    fn add_notification(&mut self, account_id: &str, message: String) {
+    /// Notifies `account_id`. `paid_by` is the account charged for the notification, which
+    /// gets the bytes back once it is dropped.
+    fn add_notification(&mut self, account_id: &str, message: String, paid_by: Option<&str>) {
         let mut user_notifications = self.notifications.get(account_id).unwrap_or_else(|| {
-            Vector::new(account_id.as_bytes())
+            RingBuffer::new(nested_prefix(NOTIFICATIONS_TAG, account_id))
         });
         
-        user_notifications.push(&message);
+        user_notifications.push_back(&Notification {
+            message,
+            paid_by: paid_by.map(str::to_string),
+        });
+        // Only the most recent notifications are kept
+        while user_notifications.len() > self.config.max_notifications {
+            self.pop_notification(&mut user_notifications);
+        }
         self.notifications.insert(account_id, &user_notifications);
     }
     
+    /// Drops the oldest notification and credits its bytes to whoever paid for it.
+    fn pop_notification(&mut self, user_notifications: &mut RingBuffer<Notification>) -> bool {
+        let initial_storage = env::storage_usage();
+        match user_notifications.pop_front() {
+            Some(notification) => {
+                self.credit_storage(notification.paid_by.as_deref(), initial_storage);
+                true
+            },
+            None => false,
+        }
+    }
+    
+    /// Notifies the author's followers who may see `record` and adds it to their home feeds.
+    fn deliver_to_followers(&mut self, record: &StatusRecord, message: String) {
+        let (author, _) = parse_status_id(&record.id).unwrap();
+        if let Some(followers) = self.followers.get(&author) {
+            for follower in followers.iter() {
+                if !self.can_view(record, Some(&follower)) {
+                    continue;
+                }
+                self.add_notification(&follower, message.clone(), Some(&author));
+                
+                let mut feed = self.home_feeds.get(&follower).unwrap_or_else(|| {
+                    Vector::new(nested_prefix(HOME_FEED_TAG, &follower))
+                });
+                feed.push(&record.id);
+                self.home_feeds.insert(&follower, &feed);
+            }
+        }
+    }
+    
     pub fn get_notifications(&self) -> Vec<String> {
-        let account_id = env::signer_account_id();
+        let account_id = env::predecessor_account_id();
         if let Some(notifications) = self.notifications.get(&account_id) {
             // Return last 10 notifications
             let len = notifications.len();
             let start_index = if len > 10 { len - 10 } else { 0 };
             (start_index..len)
-                .map(|i| notifications.get(i).unwrap())
+                .map(|i| notifications.get(i).unwrap().message)
                 .collect()
         } else {
             vec![]
         }
     }
     
-    pub fn clear_notifications(&mut self) {
-        let account_id = env::signer_account_id();
-        if self.notifications.get(&account_id).is_some() {
-            let empty_vector = Vector::new(account_id.as_bytes());
-            self.notifications.insert(&account_id, &empty_vector);
+    /// Deletes all of the caller's notifications and returns the bytes released. Each
+    /// notification's bytes are credited to the account that paid for it, not the caller.
+    #[payable]
+    pub fn clear_notifications(&mut self) -> u64 {
+        let initial_storage = env::storage_usage();
+        let initial_settled = self.settled_bytes;
+        let account_id = env::predecessor_account_id();
+        if let Some(mut user_notifications) = self.notifications.remove(&account_id) {
+            while self.pop_notification(&mut user_notifications) {}
         }
+        self.settle_storage(initial_storage, initial_settled);
+        self.charge_storage(initial_storage)
     }
     
     // Analytics
     
+    #[payable]
     pub fn view_status(&mut self, account_id: String) {
+        let initial_storage = env::storage_usage();
         // Increment view count
         let current_views = self.analytics.get(&account_id).unwrap_or(0);
         self.analytics.insert(&account_id, &(current_views + 1));
+        self.charge_storage(initial_storage);
     }
     
     pub fn get_view_count(&self, account_id: String) -> u64 {
         self.analytics.get(&account_id).unwrap_or(0)
     }
     
+    /// Current status, profile, social counts, reactions and views for one account.
+    pub fn get_account_overview(&self, account_id: String, viewer_id: Option<String>) -> AccountOverview {
+        let status = self.get_status(account_id.clone(), viewer_id);
+        AccountOverview {
+            expires_at: status.as_ref().and_then(|record| record.expires_at),
+            status,
+            profile: self.profiles.get(&account_id),
+            follower_count: self.followers.get(&account_id).map_or(0, |set| set.len()),
+            following_count: self.following.get(&account_id).map_or(0, |set| set.len()),
+            reaction_counts: self.get_reaction_counts(account_id.clone()),
+            view_count: self.get_view_count(account_id.clone()),
+            account_id,
+        }
+    }
+    
     pub fn get_dashboard_stats(&self, account_id: String) -> (u64, u64, u64) {
         // Returns (status_count, follower_count, view_count)
         let status_count = self.get_status_count(account_id.clone());
@@ -372,12 +1749,306 @@ impl StatusMessage {
         
         (status_count, follower_count, view_count)
     }
+    
+    // Migration
+    
+    /// Upgrades state written before statuses had ids. Account-wide data carries over as it
+    /// is; each account's history, reactions, notifications and followers then move over with
+    /// `migrate_accounts`, since the old maps cannot be enumerated.
+    #[init(ignore_state)]
+    #[private]
+    pub fn migrate() -> Self {
+        let old: OldStatusMessage = env::state_read()
+            .unwrap_or_else(|| env::panic_str("There is no state to migrate"));
+        Self {
+            profiles: old.profiles,
+            public_statuses: old.public_statuses,
+            status_expiry: old.status_expiry,
+            analytics: old.analytics,
+            legacy: Some(LegacyAccounts {
+                records: old.records,
+                history: old.history,
+                followers: old.followers,
+                reactions: old.reactions,
+                notifications: old.notifications,
+            }),
+            ..Self::default()
+        }
+    }
+    
+    /// Moves accounts out of the old layout, where history, reactions, notifications and
+    /// followers all shared the account id as their prefix. Old history entries get ids in
+    /// order. Entries another collection overwrote cannot be recovered and are dropped, except
+    /// the current status, which is restored from the old records. Accounts that were already
+    /// migrated are skipped.
+    pub fn migrate_accounts(&mut self, account_ids: Vec<String>) {
+        self.ensure_owner();
+        if account_ids.len() > MAX_BATCH_SIZE {
+            env::panic_str(&format!("Cannot migrate more than {} accounts at once", MAX_BATCH_SIZE));
+        }
+        let mut legacy = self.legacy.take()
+            .unwrap_or_else(|| env::panic_str("There is no legacy state to migrate"));
+        for account_id in account_ids {
+            self.migrate_account(&mut legacy, &account_id);
+        }
+        self.legacy = Some(legacy);
+    }
+    
+    fn migrate_account(&mut self, legacy: &mut LegacyAccounts, account_id: &String) {
+        let current_message = legacy.records.remove(account_id);
+        let old_history = legacy.history.remove(account_id);
+        let old_reactions = legacy.reactions.remove(account_id);
+        let old_notifications = legacy.notifications.remove(account_id);
+        let old_followers = legacy.followers.remove(account_id);
+        
+        // Read everything before clearing anything, since the old collections share keys
+        let mut history = old_history.as_ref().map_or_else(Vec::new, recover);
+        let reactions = old_reactions.as_ref().map_or_else(Vec::new, recover);
+        let notifications = old_notifications.as_ref().map_or_else(Vec::new, recover);
+        let followers = old_followers.as_ref().map_or_else(Vec::new, |set| recover(set.as_vector()));
+        if let Some(mut vector) = old_history {
+            vector.clear();
+        }
+        if let Some(mut vector) = old_reactions {
+            vector.clear();
+        }
+        if let Some(mut vector) = old_notifications {
+            vector.clear();
+        }
+        if let Some(mut set) = old_followers {
+            set.clear();
+        }
+        
+        // `records` held the current text. If its history entry was overwritten it goes back at
+        // the end; when it was posted is lost, so it gets the time of the migration.
+        let has_status = current_message.is_some();
+        if let Some(message) = current_message {
+            if history.last().map(|old| &old.message) != Some(&message) {
+                let timestamp = history.last().map_or(0, |old| old.timestamp).max(env::block_timestamp());
+                let expires_at = self.status_expiry.get(account_id);
+                history.push(OldStatusRecord { message, timestamp, expires_at });
+            }
+        }
+        
+        let len = history.len() as u64;
+        let mut latest_id = None;
+        for (seq, old) in (0..).zip(history) {
+            let mut record = StatusRecord::new(account_id, seq, old.message, old.expires_at);
+            record.timestamp = old.timestamp;
+            // Only the current status had a visibility; older ones were readable by anyone
+            if seq + 1 == len && has_status {
+                record.visibility = Visibility::resolve(None, Some(self.public_statuses.contains(account_id)));
+            }
+            let mut user_history = self.history.get(account_id).unwrap_or_else(|| {
+                RingBuffer::new(nested_prefix(HISTORY_TAG, account_id))
+            });
+            user_history.push_back(&record);
+            self.history.insert(account_id, &user_history);
+            self.reindex_mentions(&record);
+            latest_id = Some(record.id.clone());
+            if seq + 1 == len && has_status {
+                self.current_status.insert(account_id, &record.id);
+                self.index_tags(&record);
+            }
+        }
+        if len > 0 {
+            self.status_counters.insert(account_id, &len);
+        }
+        
+        // Old reactions were to whatever the account's status was, so they go to the latest one
+        if let (Some(status_id), false) = (&latest_id, reactions.is_empty()) {
+            let mut status_reactions = UnorderedMap::new(nested_prefix(REACTIONS_TAG, status_id));
+            for old in reactions {
+                status_reactions.insert(&old.account_id.clone(), &Reaction {
+                    account_id: old.account_id,
+                    reaction_type: old.reaction_type,
+                    timestamp: old.timestamp,
+                    status_id: Some(status_id.clone()),
+                });
+            }
+            self.reactions.insert(status_id, &status_reactions);
+        }
+        if !notifications.is_empty() {
+            let mut user_notifications = RingBuffer::new(nested_prefix(NOTIFICATIONS_TAG, account_id));
+            // The contract paid for these, so nobody is credited when they are dropped
+            for message in notifications {
+                user_notifications.push_back(&Notification { message, paid_by: None });
+            }
+            self.notifications.insert(account_id, &user_notifications);
+        }
+        if !followers.is_empty() {
+            let mut user_followers = UnorderedSet::new(nested_prefix(FOLLOWERS_TAG, account_id));
+            for follower_id in followers {
+                user_followers.insert(&follower_id);
+                let mut user_following = self.following.get(&follower_id).unwrap_or_else(|| {
+                    UnorderedSet::new(nested_prefix(FOLLOWING_TAG, &follower_id))
+                });
+                user_following.insert(account_id);
+                self.following.insert(&follower_id, &user_following);
+            }
+            self.followers.insert(account_id, &user_followers);
+        }
+    }
+    
+    // Storage management
+    
+    /// Registers `account_id` (the caller by default) or tops up its storage balance. With
+    /// `registration_only` anything above the minimum balance is refunded.
+    #[payable]
+    pub fn storage_deposit(&mut self, account_id: Option<String>, registration_only: Option<bool>) -> StorageBalance {
+        let amount = env::attached_deposit();
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        let registration_only = registration_only.unwrap_or(false);
+        let min_balance = storage_cost(ACCOUNT_STORAGE_BYTES);
+        
+        let mut storage = match self.storage_accounts.get(&account_id) {
+            Some(storage) if registration_only => {
+                // Already registered, so the whole deposit goes back
+                if amount > 0 {
+                    Promise::new(env::predecessor_account_id()).transfer(amount);
+                }
+                return storage.balance();
+            },
+            Some(storage) => storage,
+            None if amount < min_balance => {
+                env::panic_str("The attached deposit is less than the minimum storage balance")
+            },
+            None => StorageAccount::default(),
+        };
+        
+        if registration_only {
+            let refund = amount - min_balance;
+            if refund > 0 {
+                Promise::new(env::predecessor_account_id()).transfer(refund);
+            }
+            storage.deposit += min_balance;
+        } else {
+            storage.deposit += amount;
+        }
+        self.storage_accounts.insert(&account_id, &storage);
+        storage.balance()
+    }
+    
+    /// Withdraws `amount` of the caller's available balance, or all of it by default.
+    #[payable]
+    pub fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let mut storage = self.storage_accounts.get(&account_id)
+            .unwrap_or_else(|| env::panic_str("Account is not registered"));
+        
+        let available = storage.available();
+        let amount = amount.map_or(available, |amount| amount.0);
+        if amount > available {
+            env::panic_str("The amount is greater than the available storage balance");
+        }
+        storage.deposit -= amount;
+        self.storage_accounts.insert(&account_id, &storage);
+        if amount > 0 {
+            Promise::new(account_id).transfer(amount);
+        }
+        storage.balance()
+    }
+    
+    /// Unregisters the caller and refunds its whole deposit. Only accounts with no stored
+    /// data can unregister; delete it first. Returns false if the caller was not registered.
+    #[payable]
+    pub fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        assert_one_yocto();
+        if force.unwrap_or(false) {
+            env::panic_str("Forced unregistration is not supported");
+        }
+        let account_id = env::predecessor_account_id();
+        let storage = match self.storage_accounts.get(&account_id) {
+            Some(storage) => storage,
+            None => return false,
+        };
+        if storage.bytes_used > 0 {
+            env::panic_str("Cannot unregister an account that still has stored data");
+        }
+        
+        self.storage_accounts.remove(&account_id);
+        Promise::new(account_id).transfer(storage.deposit);
+        true
+    }
+    
+    pub fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds {
+            min: U128(storage_cost(ACCOUNT_STORAGE_BYTES)),
+            max: None,
+        }
+    }
+    
+    pub fn storage_balance_of(&self, account_id: String) -> Option<StorageBalance> {
+        self.storage_accounts.get(&account_id).map(|storage| storage.balance())
+    }
+    
+    /// Charges the caller for the storage used since `initial_storage`, or credits it back
+    /// when storage was freed. Bytes already settled with the accounts that paid for them
+    /// are left out. Any deposit attached to the call is added to the caller's balance
+    /// first, registering the caller if needed. Returns all bytes released by the call.
+    fn charge_storage(&mut self, initial_storage: u64) -> u64 {
+        self.charge_account(&env::predecessor_account_id(), initial_storage)
+    }
+    
+    /// Like `charge_storage`, but charges `account_id` instead of the caller.
+    fn charge_account(&mut self, account_id: &str, initial_storage: u64) -> u64 {
+        let account_id = account_id.to_string();
+        let total_released = initial_storage.saturating_sub(env::storage_usage());
+        let current_storage = env::storage_usage() + std::mem::take(&mut self.settled_bytes);
+        let released = initial_storage.saturating_sub(current_storage);
+        let deposit = env::attached_deposit();
+        
+        let mut storage = match self.storage_accounts.get(&account_id) {
+            Some(storage) => storage,
+            None if deposit == 0 && current_storage <= initial_storage => return total_released,
+            None if deposit == 0 => env::panic_str("Account is not registered, call storage_deposit first"),
+            None => StorageAccount::default(),
+        };
+        storage.deposit += deposit;
+        
+        if current_storage > initial_storage {
+            storage.bytes_used += current_storage - initial_storage;
+            if storage.deposit < storage.required() {
+                env::panic_str(&format!(
+                    "Insufficient storage balance: attach at least {} more yoctoNEAR",
+                    storage.required() - storage.deposit
+                ));
+            }
+        } else {
+            storage.bytes_used = storage.bytes_used.saturating_sub(released);
+        }
+        self.storage_accounts.insert(&account_id, &storage);
+        total_released
+    }
+    
+    /// Credits `paid_by` with the bytes freed since `initial_storage`, and settles them so
+    /// the caller is not credited too.
+    fn credit_storage(&mut self, paid_by: Option<&str>, initial_storage: u64) {
+        let released = initial_storage.saturating_sub(env::storage_usage());
+        if let Some(account_id) = paid_by {
+            let account_id = account_id.to_string();
+            if let Some(mut storage) = self.storage_accounts.get(&account_id) {
+                storage.bytes_used = storage.bytes_used.saturating_sub(released);
+                self.storage_accounts.insert(&account_id, &storage);
+            }
+        }
+        self.settled_bytes += released;
+    }
+    
+    /// Settles everything freed since `initial_storage`, when `settled_bytes` stood at
+    /// `initial_settled`. Bytes not credited to anyone by then, such as a collection that
+    /// held entries paid for by several accounts, are credited to nobody.
+    fn settle_storage(&mut self, initial_storage: u64, initial_settled: u64) {
+        self.settled_bytes = initial_settled + initial_storage.saturating_sub(env::storage_usage());
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 #[cfg(test)]
 mod tests {
     use super::*;
+    use legacy::OldReaction;
     use near_sdk::MockedBlockchain;
     use near_sdk::{testing_env, VMContext};
 
@@ -386,14 +2057,14 @@ mod tests {
             current_account_id: "alice_near".to_string(),
             signer_account_id: "bob_near".to_string(),
             signer_account_pk: vec![0, 1, 2],
-            predecessor_account_id: "carol_near".to_string(),
+            predecessor_account_id: "bob_near".to_string(),
             input,
             block_index: 0,
             block_timestamp: 0,
             account_balance: 0,
             account_locked_balance: 0,
             storage_usage: 0,
-            attached_deposit: 0,
+            attached_deposit: 10u128.pow(24),
             prepaid_gas: 10u64.pow(18),
             random_seed: vec![0, 1, 2],
             is_view,
@@ -407,10 +2078,10 @@ mod tests {
         let context = get_context(vec![], false);
         testing_env!(context);
         let mut contract = StatusMessage::default();
-        contract.set_status("hello".to_string(), Some(true), None);
+        contract.set_status("hello".to_string(), Some(true), None, None, None);
         assert_eq!(
             "hello".to_string(),
-            contract.get_status("bob_near".to_string()).unwrap()
+            contract.get_status("bob_near".to_string(), None).unwrap().message
         );
     }
 
@@ -419,7 +2090,7 @@ mod tests {
         let context = get_context(vec![], true);
         testing_env!(context);
         let contract = StatusMessage::default();
-        assert_eq!(None, contract.get_status("francis.near".to_string()));
+        assert!(contract.get_status("francis.near".to_string(), None).is_none());
     }
     
     #[test]
@@ -427,61 +2098,574 @@ mod tests {
         let context = get_context(vec![], false);
         testing_env!(context);
         let mut contract = StatusMessage::default();
-        contract.set_status("hello".to_string(), Some(true), None);
-        contract.set_status("world".to_string(), Some(true), None);
-        contract.set_status("test".to_string(), Some(true), None);
+        contract.set_status("hello".to_string(), Some(true), None, None, None);
+        contract.set_status("world".to_string(), Some(true), None, None, None);
+        contract.set_status("test".to_string(), Some(true), None, None, None);
         
-        let history = contract.get_status_history("bob_near".to_string());
+        let history = contract.get_status_history("bob_near".to_string(), None, None, None, None).records;
         assert_eq!(3, history.len());
-        assert_eq!("test".to_string(), contract.get_status("bob_near".to_string()).unwrap());
+        assert_eq!("test".to_string(), contract.get_status("bob_near".to_string(), None).unwrap().message);
     }
     
     #[test]
-    fn delete_status() {
+    fn status_ids_are_stable() {
         let context = get_context(vec![], false);
         testing_env!(context);
         let mut contract = StatusMessage::default();
-        contract.set_status("hello".to_string(), Some(true), None);
-        assert_eq!("hello".to_string(), contract.get_status("bob_near".to_string()).unwrap());
+        let first_id = contract.set_status("hello".to_string(), Some(true), None, None, None);
+        let second_id = contract.set_status("world".to_string(), Some(true), None, None, None);
         
-        contract.delete_status();
-        assert_eq!(None, contract.get_status("bob_near".to_string()));
+        assert_eq!("bob_near:0", first_id);
+        assert_eq!("bob_near:1", second_id);
+        assert_eq!("hello", contract.get_status_by_id(first_id, None).unwrap().message);
+        assert_eq!("world", contract.get_status_by_id(second_id, None).unwrap().message);
+        assert!(contract.get_status_by_id("bob_near:2".to_string(), None).is_none());
+        assert!(contract.get_status_by_id("not-an-id".to_string(), None).is_none());
     }
     
     #[test]
-    fn get_status_count() {
+    fn edit_status_keeps_id_and_revisions() {
         let context = get_context(vec![], false);
         testing_env!(context);
         let mut contract = StatusMessage::default();
-        contract.set_status("hello".to_string(), Some(true), None);
-        contract.set_status("world".to_string(), Some(true), None);
+        let status_id = contract.set_status("helo".to_string(), Some(true), None, None, None);
+        contract.edit_status(status_id.clone(), "hello".to_string());
         
-        assert_eq!(2, contract.get_status_count("bob_near".to_string()));
+        let record = contract.get_status_by_id(status_id.clone(), None).unwrap();
+        assert_eq!("hello", record.message);
+        assert!(record.edited);
+        assert_eq!("hello", contract.get_status("bob_near".to_string(), None).unwrap().message);
+        assert_eq!(1, contract.get_status_count("bob_near".to_string()));
+        
+        let revisions = contract.get_status_revisions(status_id, None);
+        assert_eq!(2, revisions.len());
+        assert_eq!("helo", revisions[0].message);
+        assert_eq!("hello", revisions[1].message);
     }
     
     #[test]
-    fn set_profile() {
+    fn revisions_follow_visibility() {
         let context = get_context(vec![], false);
         testing_env!(context);
         let mut contract = StatusMessage::default();
-        contract.set_profile("Alice".to_string(), "Blockchain enthusiast".to_string());
+        let viewer = |account_id: &str| Some(account_id.to_string());
+        let status_id = contract.set_status("secret".to_string(), None, None, None, Some(Visibility::FollowersOnly));
+        contract.edit_status(status_id.clone(), "still secret".to_string());
         
-        let profile = contract.get_profile("bob_near".to_string()).unwrap();
-        assert_eq!("Alice", profile.name);
-        assert_eq!("Blockchain enthusiast", profile.bio);
+        assert!(contract.get_status_revisions(status_id.clone(), None).is_empty());
+        assert!(contract.get_status_revisions(status_id.clone(), viewer("alice_near")).is_empty());
+        assert_eq!(2, contract.get_status_revisions(status_id, viewer("bob_near")).len());
     }
     
     #[test]
-    fn public_statuses() {
+    fn replies() {
         let context = get_context(vec![], false);
         testing_env!(context);
         let mut contract = StatusMessage::default();
-        contract.set_status("Public status".to_string(), Some(true), None);
-        contract.set_status("Private status".to_string(), Some(false), None);
+        let status_id = contract.set_status("Hello world".to_string(), Some(true), None, None, None);
         
-        let public = contract.get_public_statuses();
-        assert_eq!(1, public.len());
-        assert_eq!("Public status", public[0].1);
+        let first_reply = contract.reply_to(status_id.clone(), "Hi everyone".to_string());
+        contract.reply_to(status_id.clone(), "Welcome".to_string());
+        
+        assert_eq!(2, contract.get_status_by_id(status_id.clone(), None).unwrap().reply_count);
+        let replies = contract.get_replies(status_id.clone(), 0, 10, None);
+        assert_eq!(2, replies.len());
+        assert_eq!(first_reply, replies[0].id);
+        assert_eq!(Some(status_id.clone()), replies[0].reply_to);
+        assert_eq!("Welcome", contract.get_replies(status_id, 1, 10, None)[0].message);
+        assert_eq!("Hello world", contract.get_status("bob_near".to_string(), None).unwrap().message);
+    }
+    
+    #[test]
+    fn reposts_and_quotes() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::default();
+        let status_id = contract.set_status("Hello world".to_string(), Some(true), None, None, None);
+        
+        let repost_id = contract.repost(status_id.clone(), None);
+        let quote_id = contract.repost(repost_id.clone(), Some("Worth reading".to_string()));
+        
+        let repost = contract.get_status_by_id(repost_id, None).unwrap();
+        assert_eq!(Some(status_id.clone()), repost.repost_of);
+        let quote = contract.get_status_by_id(quote_id, None).unwrap();
+        assert_eq!(Some(status_id.clone()), quote.repost_of);
+        assert_eq!("Worth reading", quote.message);
+        assert_eq!(2, contract.get_status_by_id(status_id, None).unwrap().repost_count);
+        assert_eq!(3, contract.get_status_count("bob_near".to_string()));
+    }
+    
+    #[test]
+    fn mentions() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::default();
+        let status_id = contract.set_status("Thanks @alice.near and @carol_near, not @Invalid or me@dave.near".to_string(), Some(true), None, None, None);
+        
+        let alice_mentions = contract.get_mentions("alice.near".to_string(), 0, 10, None);
+        assert_eq!(1, alice_mentions.len());
+        assert_eq!(status_id, alice_mentions[0].id);
+        assert_eq!(1, contract.get_mentions("carol_near".to_string(), 0, 10, None).len());
+        assert_eq!(0, contract.get_mentions("dave.near".to_string(), 0, 10, None).len());
+        
+        let extracted = extract_mentions("@a1 @a2 @a3 @a4 @a5 @a6 @a7 @a8 @a9 @b1 @b2 @b1");
+        assert_eq!(mentions::MAX_MENTIONS_PER_STATUS, extracted.len());
+        assert_eq!(vec!["alice.near".to_string()], extract_mentions("cc @alice.near."));
+    }
+    
+    #[test]
+    fn editing_updates_mentions() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::default();
+        let status_id = contract.set_status("Hi @alice.near".to_string(), Some(true), None, None, None);
+        
+        contract.edit_status(status_id.clone(), "Hi @carol_near".to_string());
+        assert_eq!(0, contract.get_mentions("alice.near".to_string(), 0, 10, None).len());
+        assert_eq!(status_id, contract.get_mentions("carol_near".to_string(), 0, 10, None)[0].id);
+        
+        contract.delete_status(Some(true));
+        assert_eq!(0, contract.get_mentions("carol_near".to_string(), 0, 10, None).len());
+        assert!(contract.mentions.get(&"carol_near".to_string()).is_none());
+    }
+    
+    #[test]
+    fn hashtags() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::default();
+        let first_id = contract.set_status("Shipping #NEAR #rust today".to_string(), Some(true), None, None, None);
+        contract.set_status("More #near news".to_string(), Some(true), None, None, None);
+        
+        assert_eq!(2, contract.get_tag_count("near".to_string()));
+        assert_eq!(1, contract.get_tag_count("#Rust".to_string()));
+        let tagged = contract.get_statuses_by_tag("near".to_string(), 0, 1, None);
+        assert_eq!(1, tagged.len());
+        assert_eq!(first_id, tagged[0].id);
+        
+        // Deleting the current status removes it from the index
+        contract.delete_status(None);
+        assert_eq!(1, contract.get_tag_count("near".to_string()));
+        assert_eq!(first_id, contract.get_statuses_by_tag("near".to_string(), 0, 10, None)[0].id);
+    }
+    
+    #[test]
+    fn attachments() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::default();
+        let image = Attachment {
+            content: "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_string(),
+            mime_type: "image/png".to_string(),
+            size: 2048,
+            alt_text: Some("A sunset".to_string()),
+            content_hash: None,
+        };
+        contract.set_status("Look at this".to_string(), Some(true), None, Some(vec![image]), None);
+        
+        let status = contract.get_status("bob_near".to_string(), None).unwrap();
+        assert_eq!(1, status.attachments.len());
+        assert_eq!("image/png", status.attachments[0].mime_type);
+        assert_eq!(1, contract.get_public_statuses(None, None).statuses[0].1.attachments.len());
+        assert_eq!(1, contract.get_status_history("bob_near".to_string(), None, None, None, None).records[0].attachments.len());
+    }
+    
+    #[test]
+    #[should_panic]
+    fn too_many_attachments() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::default();
+        let image = Attachment {
+            content: "https://example.com/cat.png".to_string(),
+            mime_type: "image/png".to_string(),
+            size: 2048,
+            alt_text: None,
+            content_hash: None,
+        };
+        contract.set_status("Cats".to_string(), Some(true), None, Some(vec![image; attachments::MAX_ATTACHMENTS + 1]), None);
+    }
+    
+    #[test]
+    fn scheduled_status() {
+        let context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = StatusMessage::default();
+        contract.set_status("Now".to_string(), Some(true), None, None, None);
+        let later = contract.schedule_status("Later".to_string(), 100, Some(true), None, None, None);
+        let cancelled = contract.schedule_status("Never".to_string(), 200, Some(true), None, None, None);
+        contract.cancel_scheduled_status(cancelled);
+        
+        assert_eq!("Now", contract.get_status("bob_near".to_string(), None).unwrap().message);
+        let pending = contract.get_scheduled_statuses("bob_near".to_string(), Some("bob_near".to_string()));
+        assert_eq!(1, pending.len());
+        assert!(contract.get_scheduled_statuses("bob_near".to_string(), None).is_empty());
+        assert_eq!(later, pending[0].id);
+        
+        // Due posts are visible to views before any write publishes them
+        let mut context_later = context.clone();
+        context_later.block_timestamp = 150;
+        context_later.storage_usage = env::storage_usage();
+        testing_env!(context_later.clone());
+        let status = contract.get_status("bob_near".to_string(), None).unwrap();
+        assert_eq!("Later", status.message);
+        assert_eq!("Later", contract.get_public_statuses(None, None).statuses[0].1.message);
+        assert_eq!(0, contract.get_scheduled_statuses("bob_near".to_string(), Some("bob_near".to_string())).len());
+        
+        // Anyone can publish, but the author pays
+        let bob_used = contract.storage_accounts.get(&"bob_near".to_string()).unwrap().bytes_used;
+        let mut context_carol = context_later.clone();
+        context_carol.predecessor_account_id = "carol_near".to_string();
+        context_carol.attached_deposit = 0;
+        testing_env!(context_carol);
+        contract.publish_scheduled("bob_near".to_string());
+        assert!(contract.storage_accounts.get(&"carol_near".to_string()).is_none());
+        assert_ne!(bob_used, contract.storage_accounts.get(&"bob_near".to_string()).unwrap().bytes_used);
+        assert_eq!(status.id, contract.get_status("bob_near".to_string(), None).unwrap().id);
+        // Published late, so it is stamped when it was published rather than when it was due
+        assert_eq!(150, contract.get_status_by_id(status.id, None).unwrap().timestamp);
+        assert_eq!(2, contract.get_status_count("bob_near".to_string()));
+    }
+    
+    #[test]
+    fn pinned_statuses() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::default();
+        let first_id = contract.set_status("First".to_string(), Some(true), None, None, None);
+        let second_id = contract.set_status("Second".to_string(), Some(true), None, None, None);
+        contract.set_status("Third".to_string(), Some(true), None, None, None);
+        
+        contract.pin_status(first_id.clone());
+        contract.pin_status(second_id.clone());
+        let pinned = contract.get_pinned("bob_near".to_string(), None);
+        assert_eq!(2, pinned.len());
+        assert_eq!(second_id, pinned[0].id);
+        assert_eq!(first_id, pinned[1].id);
+        
+        contract.unpin_status(second_id);
+        assert_eq!(1, contract.get_pinned("bob_near".to_string(), None).len());
+    }
+    
+    #[test]
+    fn repinning_credits_the_deposit() {
+        let context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = StatusMessage::default();
+        let status_id = contract.set_status("First".to_string(), Some(true), None, None, None);
+        contract.pin_status(status_id.clone());
+        let deposit = contract.storage_accounts.get(&"bob_near".to_string()).unwrap().deposit;
+        
+        contract.pin_status(status_id);
+        assert_eq!(deposit + context.attached_deposit, contract.storage_accounts.get(&"bob_near".to_string()).unwrap().deposit);
+    }
+    
+    #[test]
+    #[should_panic(expected = "Cannot pin more than 1 statuses")]
+    fn pin_limit_is_configurable() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::new("bob_near".to_string(), Some(Config {
+            max_pinned: 1,
+            ..Config::default()
+        }));
+        let first_id = contract.set_status("First".to_string(), Some(true), None, None, None);
+        let second_id = contract.set_status("Second".to_string(), Some(true), None, None, None);
+        contract.pin_status(first_id);
+        contract.pin_status(second_id);
+    }
+    
+    #[test]
+    fn paginated_history() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::default();
+        for i in 0..5 {
+            contract.set_status(format!("status {}", i), Some(true), None, None, None);
+        }
+        let bob = || "bob_near".to_string();
+        
+        let page = contract.get_status_history(bob(), None, Some(2), None, None);
+        assert_eq!(vec!["status 4", "status 3"], page.records.iter().map(|r| r.message.as_str()).collect::<Vec<_>>());
+        assert_eq!(Some(2), page.next_index);
+        let page = contract.get_status_history(bob(), page.next_index, Some(2), None, None);
+        assert_eq!("status 2", page.records[0].message);
+        let page = contract.get_status_history(bob(), page.next_index, Some(2), None, None);
+        assert_eq!(1, page.records.len());
+        assert_eq!(None, page.next_index);
+        
+        let page = contract.get_status_history(bob(), Some(3), Some(10), Some(SortOrder::Ascending), None);
+        assert_eq!(vec!["status 3", "status 4"], page.records.iter().map(|r| r.message.as_str()).collect::<Vec<_>>());
+        assert_eq!(None, page.next_index);
+        
+        let page = contract.get_status_history(bob(), None, Some(1000), None, None);
+        assert_eq!(5, page.records.len());
+    }
+    
+    #[test]
+    fn history_between_timestamps() {
+        let context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = StatusMessage::default();
+        for i in 0..5 {
+            let mut context_at = context.clone();
+            context_at.block_timestamp = i * 100;
+            context_at.storage_usage = env::storage_usage();
+            testing_env!(context_at);
+            contract.set_status(format!("status {}", i), Some(true), None, None, None);
+        }
+        let bob = || "bob_near".to_string();
+        
+        let between = contract.get_status_history_between(bob(), 100, 300, None, None);
+        assert_eq!(vec!["status 1", "status 2", "status 3"], between.iter().map(|r| r.message.as_str()).collect::<Vec<_>>());
+        assert_eq!(1, contract.get_status_history_between(bob(), 150, 1000, Some(1), None).len());
+        assert_eq!(0, contract.get_status_history_between(bob(), 401, 1000, None, None).len());
+    }
+    
+    #[test]
+    fn retention_policy() {
+        let context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = StatusMessage::default();
+        let bob = || "bob_near".to_string();
+        let first_id = contract.set_status("#old news".to_string(), Some(true), None, None, None);
+        contract.set_status("second".to_string(), Some(true), None, None, None);
+        contract.set_status("third".to_string(), Some(true), None, None, None);
+        
+        contract.set_retention_policy(Some(RetentionPolicy::KeepLast(2)));
+        assert_eq!(Some(RetentionPolicy::KeepLast(2)), contract.get_retention_policy(bob()));
+        assert_eq!(2, contract.get_status_count(bob()));
+        assert!(contract.get_status_by_id(first_id, None).is_none());
+        assert_eq!(0, contract.get_tag_count("old".to_string()));
+        
+        contract.set_status("fourth".to_string(), Some(true), None, None, None);
+        let history = contract.get_status_history(bob(), None, None, Some(SortOrder::Ascending), None).records;
+        assert_eq!(vec!["third", "fourth"], history.iter().map(|r| r.message.as_str()).collect::<Vec<_>>());
+        
+        // Entries older than the window are pruned, but the current status is kept
+        contract.set_retention_policy(Some(RetentionPolicy::KeepDays(1)));
+        let mut context_later = context.clone();
+        context_later.block_timestamp = 2 * history::NANOS_PER_DAY;
+        context_later.storage_usage = env::storage_usage();
+        testing_env!(context_later);
+        contract.reply_to(history[1].id.clone(), "still here".to_string());
+        assert_eq!(2, contract.get_status_count(bob()));
+        let current = contract.get_status(bob(), None).unwrap();
+        assert_eq!("fourth", current.message);
+        assert_eq!(1, current.reply_count);
+    }
+    
+    #[test]
+    fn delete_status() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::default();
+        contract.set_status("hello".to_string(), Some(true), None, None, None);
+        assert_eq!("hello".to_string(), contract.get_status("bob_near".to_string(), None).unwrap().message);
+        
+        contract.delete_status(None);
+        assert!(contract.get_status("bob_near".to_string(), None).is_none());
+    }
+    
+    #[test]
+    fn delete_single_status_and_purge_history() {
+        let context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = StatusMessage::default();
+        let bob = || "bob_near".to_string();
+        let first_id = contract.set_status("Hello #near @alice.near".to_string(), Some(true), None, None, None);
+        let reply_id = contract.reply_to(first_id.clone(), "self reply".to_string());
+        contract.pin_status(first_id.clone());
+        let second_id = contract.set_status("Second".to_string(), Some(true), None, None, None);
+        
+        contract.delete_status_by_id(first_id.clone());
+        assert_eq!(0, contract.get_tag_count("near".to_string()));
+        assert_eq!(0, contract.get_mentions("alice.near".to_string(), 0, 10, None).len());
+        assert_eq!(0, contract.get_pinned(bob(), None).len());
+        assert_eq!(second_id, contract.get_status(bob(), None).unwrap().id);
+        
+        // Once the restore window passes the tombstone loses its content and related data
+        let mut context_later = context.clone();
+        context_later.block_timestamp = Config::default().restore_window() + 1;
+        context_later.storage_usage = env::storage_usage();
+        testing_env!(context_later);
+        contract.set_status("Third".to_string(), Some(true), None, None, None);
+        let tombstone = contract.get_status_by_id(first_id.clone(), None).unwrap();
+        assert!(tombstone.is_deleted());
+        assert_eq!(0, tombstone.reply_count);
+        assert_eq!(0, contract.get_replies(first_id, 0, 10, None).len());
+        assert!(contract.get_status_by_id(reply_id, None).is_some());
+        
+        contract.delete_status(Some(true));
+        assert_eq!(0, contract.get_status_count(bob()));
+        assert!(contract.get_status(bob(), None).is_none());
+        
+        // Ids are never reused after a purge
+        assert_eq!("bob_near:4", contract.set_status("Fresh start".to_string(), Some(true), None, None, None));
+    }
+    
+    #[test]
+    fn tombstones_and_restore() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::default();
+        let status_id = contract.set_status("Oops #typo".to_string(), Some(true), None, None, None);
+        contract.pin_status(status_id.clone());
+        
+        contract.delete_status_by_id(status_id.clone());
+        let tombstone = contract.get_status_by_id(status_id.clone(), None).unwrap();
+        let deleted = tombstone.deleted.unwrap();
+        assert_eq!(DeletedBy::Author, deleted.deleted_by);
+        assert_eq!("", tombstone.message);
+        assert!(contract.get_status_revisions(status_id.clone(), None).is_empty());
+        assert_eq!(1, contract.get_status_history("bob_near".to_string(), None, None, None, None).records.len());
+        assert!(contract.get_status("bob_near".to_string(), None).is_none());
+        
+        assert!(contract.get_pinned("bob_near".to_string(), None).is_empty());
+        
+        contract.restore_status(status_id.clone());
+        let restored = contract.get_status_by_id(status_id.clone(), None).unwrap();
+        assert!(!restored.is_deleted());
+        assert_eq!("Oops #typo", restored.message);
+        assert_eq!(1, contract.get_tag_count("typo".to_string()));
+        assert_eq!(status_id, contract.get_status("bob_near".to_string(), None).unwrap().id);
+        assert_eq!(1, contract.get_public_statuses(None, None).statuses.len());
+        assert_eq!(status_id, contract.get_pinned("bob_near".to_string(), None)[0].id);
+    }
+    
+    #[test]
+    fn get_status_count() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::default();
+        contract.set_status("hello".to_string(), Some(true), None, None, None);
+        contract.set_status("world".to_string(), Some(true), None, None, None);
+        
+        assert_eq!(2, contract.get_status_count("bob_near".to_string()));
+    }
+    
+    #[test]
+    fn set_profile() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::default();
+        contract.set_profile("Alice".to_string(), "Blockchain enthusiast".to_string());
+        
+        let profile = contract.get_profile("bob_near".to_string()).unwrap();
+        assert_eq!("Alice", profile.name);
+        assert_eq!("Blockchain enthusiast", profile.bio);
+    }
+    
+    #[test]
+    fn public_statuses() {
+        let context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = StatusMessage::default();
+        contract.set_status("Public status".to_string(), Some(true), None, None, None);
+        
+        let mut context_alice = context.clone();
+        context_alice.predecessor_account_id = "alice_near".to_string();
+        context_alice.storage_usage = env::storage_usage();
+        testing_env!(context_alice);
+        contract.set_status("Private status".to_string(), Some(false), None, None, None);
+        
+        let public = contract.get_public_statuses(None, None).statuses;
+        assert_eq!(1, public.len());
+        assert_eq!("Public status", public[0].1.message);
+    }
+    
+    #[test]
+    fn visibility() {
+        let context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = StatusMessage::default();
+        
+        let mut context_alice = context.clone();
+        context_alice.predecessor_account_id = "alice_near".to_string();
+        testing_env!(context_alice);
+        contract.follow("bob_near".to_string());
+        
+        let mut context_bob = context.clone();
+        context_bob.storage_usage = env::storage_usage();
+        testing_env!(context_bob);
+        let followers_only = contract.set_status("For followers".to_string(), None, None, None, Some(Visibility::FollowersOnly));
+        let bob = || "bob_near".to_string();
+        let viewer = |account_id: &str| Some(account_id.to_string());
+        
+        assert!(contract.get_status(bob(), viewer("alice_near")).is_some());
+        assert!(contract.get_status(bob(), viewer("carol_near")).is_none());
+        assert!(contract.get_status(bob(), None).is_none());
+        assert!(contract.get_status(bob(), viewer("bob_near")).is_some());
+        assert!(contract.get_status_by_id(followers_only, viewer("carol_near")).is_none());
+        assert_eq!(0, contract.get_public_statuses(None, None).statuses.len());
+        
+        contract.add_to_list("close".to_string(), "carol_near".to_string());
+        contract.set_status("For close friends".to_string(), None, None, None, Some(Visibility::List("close".to_string())));
+        assert!(contract.get_status(bob(), viewer("carol_near")).is_some());
+        assert!(contract.get_status(bob(), viewer("alice_near")).is_none());
+        
+        contract.set_status("Just me".to_string(), Some(false), None, None, None);
+        assert!(contract.get_status(bob(), viewer("alice_near")).is_none());
+        assert_eq!(1, contract.get_status_history(bob(), None, None, None, viewer("alice_near")).records.len());
+        assert_eq!(3, contract.get_status_history(bob(), None, None, None, viewer("bob_near")).records.len());
+    }
+    
+    #[test]
+    fn timeline() {
+        let context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = StatusMessage::default();
+        contract.set_status("one".to_string(), Some(true), None, None, None);
+        contract.set_status("hidden".to_string(), Some(false), None, None, None);
+        let deleted_id = contract.set_status("deleted".to_string(), Some(true), None, None, None);
+        contract.delete_status_by_id(deleted_id);
+        
+        let mut context_alice = context.clone();
+        context_alice.predecessor_account_id = "alice_near".to_string();
+        context_alice.storage_usage = env::storage_usage();
+        testing_env!(context_alice);
+        contract.set_status("two".to_string(), Some(true), None, None, None);
+        contract.set_status("three".to_string(), Some(true), None, None, None);
+        
+        let page = contract.get_timeline(None, Some(2));
+        assert_eq!(vec!["three", "two"], page.records.iter().map(|r| r.message.as_str()).collect::<Vec<_>>());
+        let page = contract.get_timeline(page.next_index, Some(2));
+        assert_eq!(vec!["one"], page.records.iter().map(|r| r.message.as_str()).collect::<Vec<_>>());
+        assert_eq!(None, page.next_index);
+    }
+    
+    #[test]
+    fn home_feed() {
+        let context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = StatusMessage::default();
+        
+        let mut context_alice = context.clone();
+        context_alice.predecessor_account_id = "alice_near".to_string();
+        testing_env!(context_alice.clone());
+        contract.follow("bob_near".to_string());
+        contract.follow("carol_near".to_string());
+        assert_eq!(2, contract.get_following().len());
+        
+        for (author, message) in vec![("bob_near", "bob 1"), ("carol_near", "carol 1"), ("bob_near", "bob 2")] {
+            let mut context_author = context.clone();
+            context_author.predecessor_account_id = author.to_string();
+            context_author.storage_usage = env::storage_usage();
+            testing_env!(context_author);
+            contract.set_status(message.to_string(), Some(true), None, None, None);
+        }
+        contract.set_status("bob private".to_string(), Some(false), None, None, None);
+        
+        let page = contract.get_home_feed("alice_near".to_string(), None, Some(2));
+        assert_eq!(vec!["bob 2", "carol 1"], page.records.iter().map(|r| r.message.as_str()).collect::<Vec<_>>());
+        let page = contract.get_home_feed("alice_near".to_string(), page.next_index, Some(2));
+        assert_eq!(vec!["bob 1"], page.records.iter().map(|r| r.message.as_str()).collect::<Vec<_>>());
+        
+        context_alice.storage_usage = env::storage_usage();
+        testing_env!(context_alice);
+        contract.unfollow("bob_near".to_string());
+        let page = contract.get_home_feed("alice_near".to_string(), None, None);
+        assert_eq!(vec!["carol 1"], page.records.iter().map(|r| r.message.as_str()).collect::<Vec<_>>());
     }
     
     #[test]
@@ -490,9 +2674,9 @@ mod tests {
         testing_env!(context.clone());
         let mut contract = StatusMessage::default();
         
-        // Change signer for alice
+        // Change caller to alice
         let mut context_alice = context.clone();
-        context_alice.signer_account_id = "alice_near".to_string();
+        context_alice.predecessor_account_id = "alice_near".to_string();
         testing_env!(context_alice);
         
         contract.follow("bob_near".to_string());
@@ -510,12 +2694,451 @@ mod tests {
         let context = get_context(vec![], false);
         testing_env!(context);
         let mut contract = StatusMessage::default();
-        contract.set_status("Hello world".to_string(), Some(true), None);
-        contract.set_status("Rust programming".to_string(), Some(true), None);
+        contract.set_status("Hello world".to_string(), Some(true), None, None, None);
+        contract.set_status("Rust programming".to_string(), Some(true), None, None, None);
         
-        let results = contract.search_status("rust".to_string());
+        let results = contract.search_status("rust".to_string(), None, None).statuses;
         assert_eq!(1, results.len());
-        assert_eq!("Rust programming", results[0].1);
+        assert_eq!("Rust programming", results[0].1.message);
+    }
+    
+    #[test]
+    #[should_panic(expected = "Message cannot be longer than 1024 bytes")]
+    fn message_too_long() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::default();
+        contract.set_status("a".repeat(1025), None, None, None, None);
+    }
+    
+    #[test]
+    fn expired_statuses_leave_tag_index() {
+        let context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = StatusMessage::default();
+        contract.set_status("#flash sale".to_string(), None, Some(1), None, None);
+        contract.set_status("#news later".to_string(), None, None, None, None);
+        assert_eq!(1, contract.get_tag_count("flash".to_string()));
+        
+        let mut context_later = context;
+        context_later.block_timestamp = 2 * 3600 * 1_000_000_000;
+        context_later.storage_usage = env::storage_usage();
+        testing_env!(context_later);
+        assert!(contract.get_statuses_by_tag("flash".to_string(), 0, 10, None).is_empty());
+        contract.set_status("#news again".to_string(), None, None, None, None);
+        assert_eq!(0, contract.get_tag_count("flash".to_string()));
+        assert_eq!(2, contract.get_tag_count("news".to_string()));
+        assert!(contract.expiring.get(&"bob_near".to_string()).is_none());
+    }
+    
+    #[test]
+    fn retention_prunes_past_current_status() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::default();
+        let bob = || "bob_near".to_string();
+        let status_id = contract.set_status("only".to_string(), None, None, None, None);
+        contract.set_retention_policy(Some(RetentionPolicy::KeepLast(2)));
+        
+        for i in 0..4 {
+            contract.reply_to(status_id.clone(), format!("reply {}", i));
+        }
+        assert_eq!(2, contract.get_status_count(bob()));
+        let history = contract.get_status_history(bob(), None, None, Some(SortOrder::Ascending), None).records;
+        assert_eq!(vec!["only", "reply 3"], history.iter().map(|r| r.message.as_str()).collect::<Vec<_>>());
+        assert_eq!("only", contract.get_status(bob(), None).unwrap().message);
+    }
+    
+    #[test]
+    fn configurable_limits() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::new("bob_near".to_string(), None);
+        contract.set_config(Config {
+            max_history_entries: 2,
+            max_notifications: 1,
+            ..Config::default()
+        });
+        assert_eq!(2, contract.get_config().max_history_entries);
+        
+        for message in vec!["one", "two", "three"] {
+            contract.set_status(message.to_string(), None, None, None, None);
+        }
+        let history = contract.get_status_history("bob_near".to_string(), None, None, None, None);
+        assert_eq!(vec!["three", "two"], history.records.iter().map(|r| r.message.as_str()).collect::<Vec<_>>());
+        
+        contract.follow("alice_near".to_string());
+        contract.set_status("Hi @alice_near".to_string(), None, None, None, None);
+        let mut context_alice = get_context(vec![], false);
+        context_alice.predecessor_account_id = "alice_near".to_string();
+        context_alice.storage_usage = env::storage_usage();
+        testing_env!(context_alice);
+        assert_eq!(vec!["bob_near mentioned you in a status".to_string()], contract.get_notifications());
+    }
+    
+    #[test]
+    #[should_panic(expected = "List name cannot be longer than 4 bytes")]
+    fn list_visibility_names_are_capped() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::new("carol_near".to_string(), Some(Config {
+            max_name_bytes: 4,
+            ..Config::default()
+        }));
+        contract.set_profile("Bob".to_string(), String::new());
+        contract.add_to_list("team".to_string(), "alice_near".to_string());
+        contract.set_status("Hi team".to_string(), None, None, None, Some(Visibility::List("teams".to_string())));
+    }
+    
+    #[test]
+    #[should_panic(expected = "Config limits must be greater than zero")]
+    fn invalid_config() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        StatusMessage::new("bob_near".to_string(), Some(Config {
+            max_message_bytes: 0,
+            ..Config::default()
+        }));
+    }
+    
+    #[test]
+    #[should_panic(expected = "restore_window_hours is too large")]
+    fn restore_window_cannot_overflow() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        StatusMessage::new("bob_near".to_string(), Some(Config {
+            restore_window_hours: u64::MAX,
+            ..Config::default()
+        }));
+    }
+    
+    #[test]
+    fn owner_transfer() {
+        let context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = StatusMessage::new("bob_near".to_string(), None);
+        assert_eq!("bob_near", contract.get_owner());
+        
+        contract.propose_owner(Some("dave_near".to_string()));
+        assert_eq!(Some("dave_near".to_string()), contract.get_pending_owner());
+        assert_eq!("bob_near", contract.get_owner());
+        
+        let mut context_dave = context.clone();
+        context_dave.predecessor_account_id = "dave_near".to_string();
+        testing_env!(context_dave);
+        contract.accept_owner();
+        assert_eq!("dave_near", contract.get_owner());
+        assert!(contract.get_pending_owner().is_none());
+        contract.set_config(Config { restore_window_hours: 1, ..Config::default() });
+        assert_eq!(3600 * 1_000_000_000, contract.get_config().restore_window());
+    }
+    
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn admin_methods_require_owner() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        // Without `new` the contract account owns the contract
+        let mut contract = StatusMessage::default();
+        assert_eq!("alice_near", contract.get_owner());
+        contract.set_config(Config::default());
+    }
+    
+    #[test]
+    fn nested_collections_do_not_collide() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::default();
+        contract.set_status("Hello".to_string(), None, None, None, None);
+        contract.add_reaction("bob_near".to_string(), "like".to_string());
+        contract.follow("alice_near".to_string());
+        
+        assert_eq!("Hello", contract.history.get(&"bob_near".to_string()).unwrap().get(0).unwrap().message);
+        assert_eq!("like", contract.get_reactions("bob_near".to_string())[0].reaction_type);
+        assert_eq!(1, contract.get_notifications().len());
+    }
+    
+    #[test]
+    fn migrate_from_old_layout() {
+        let context = get_context(vec![], false);
+        let mut context_owner = context.clone();
+        context_owner.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context_owner);
+        let bob = || "bob_near".to_string();
+        let alice = || "alice_near".to_string();
+        
+        // State as the contract wrote it before statuses had ids
+        let mut old = OldStatusMessage {
+            records: LookupMap::new(b"r".to_vec()),
+            history: LookupMap::new(b"h".to_vec()),
+            profiles: LookupMap::new(b"p".to_vec()),
+            public_statuses: UnorderedSet::new(b"s".to_vec()),
+            followers: LookupMap::new(b"f".to_vec()),
+            reactions: LookupMap::new(b"re".to_vec()),
+            notifications: LookupMap::new(b"n".to_vec()),
+            status_expiry: UnorderedMap::new(b"e".to_vec()),
+            analytics: LookupMap::new(b"a".to_vec()),
+        };
+        let mut history = Vector::new(bob().as_bytes());
+        history.push(&OldStatusRecord { message: "First".to_string(), timestamp: 1, expires_at: None });
+        history.push(&OldStatusRecord { message: "Hi @carol_near #near".to_string(), timestamp: 2, expires_at: None });
+        old.history.insert(&bob(), &history);
+        old.records.insert(&bob(), &"Hi @carol_near #near".to_string());
+        old.public_statuses.insert(&bob());
+        // Reactions shared the history prefix, so this overwrites "First"
+        let mut reactions = Vector::new(bob().as_bytes());
+        reactions.push(&OldReaction { account_id: alice(), reaction_type: "like".to_string(), timestamp: 3 });
+        old.reactions.insert(&bob(), &reactions);
+        let mut followers = UnorderedSet::new(bob().as_bytes());
+        followers.insert(&alice());
+        old.followers.insert(&bob(), &followers);
+        old.profiles.insert(&bob(), &UserProfile::default());
+        env::state_write(&old);
+        
+        let mut contract = StatusMessage::migrate();
+        assert!(contract.get_profile(bob()).is_some());
+        assert!(contract.get_status(bob(), None).is_none());
+        contract.migrate_accounts(vec![bob(), alice()]);
+        contract.migrate_accounts(vec![bob()]);
+        
+        let status = contract.get_status(bob(), None).unwrap();
+        assert_eq!("bob_near:0", status.id);
+        assert_eq!("Hi @carol_near #near", status.message);
+        assert_eq!(2, status.timestamp);
+        assert_eq!(1, contract.get_status_count(bob()));
+        assert_eq!(Some(status.id.clone()), contract.get_reactions(bob())[0].status_id);
+        assert_eq!(vec![alice()], contract.get_followers(bob()));
+        assert!(contract.following.get(&alice()).unwrap().contains(&bob()));
+        assert_eq!(1, contract.get_mentions("carol_near".to_string(), 0, 10, None).len());
+        assert_eq!(1, contract.get_tag_count("near".to_string()));
+        assert!(!env::storage_has_key(&[bob().as_bytes(), &1u64.to_le_bytes()].concat()));
+        
+        let mut context_bob = context;
+        context_bob.storage_usage = env::storage_usage();
+        testing_env!(context_bob);
+        assert_eq!("bob_near:1", contract.set_status("After".to_string(), None, None, None, None));
+    }
+    
+    #[test]
+    fn migrate_restores_overwritten_current_status() {
+        let mut context = get_context(vec![], false);
+        context.predecessor_account_id = context.current_account_id.clone();
+        context.block_timestamp = 10;
+        testing_env!(context);
+        let bob = || "bob_near".to_string();
+        
+        let mut old = OldStatusMessage {
+            records: LookupMap::new(b"r".to_vec()),
+            history: LookupMap::new(b"h".to_vec()),
+            profiles: LookupMap::new(b"p".to_vec()),
+            public_statuses: UnorderedSet::new(b"s".to_vec()),
+            followers: LookupMap::new(b"f".to_vec()),
+            reactions: LookupMap::new(b"re".to_vec()),
+            notifications: LookupMap::new(b"n".to_vec()),
+            status_expiry: UnorderedMap::new(b"e".to_vec()),
+            analytics: LookupMap::new(b"a".to_vec()),
+        };
+        let mut history = Vector::new(bob().as_bytes());
+        history.push(&OldStatusRecord { message: "First".to_string(), timestamp: 1, expires_at: None });
+        history.push(&OldStatusRecord { message: "Second".to_string(), timestamp: 2, expires_at: None });
+        old.history.insert(&bob(), &history);
+        old.records.insert(&bob(), &"Second".to_string());
+        old.public_statuses.insert(&bob());
+        // Another collection sharing the prefix overwrote the last entry
+        env::storage_write(&[bob().as_bytes(), &1u64.to_le_bytes()].concat(), b"clobbered");
+        env::state_write(&old);
+        
+        let mut contract = StatusMessage::migrate();
+        contract.migrate_accounts(vec![bob()]);
+        let status = contract.get_status(bob(), None).unwrap();
+        assert_eq!("Second", status.message);
+        assert_eq!("bob_near:1", status.id);
+        assert_eq!(10, status.timestamp);
+        let history = contract.get_status_history(bob(), None, None, Some(SortOrder::Ascending), None).records;
+        assert_eq!(vec!["First", "Second"], history.iter().map(|r| r.message.as_str()).collect::<Vec<_>>());
+    }
+    
+    #[test]
+    fn storage_deposit_and_charging() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 0;
+        testing_env!(context.clone());
+        let mut contract = StatusMessage::default();
+        assert!(contract.storage_balance_of("bob_near".to_string()).is_none());
+        
+        let mut context_deposit = context.clone();
+        context_deposit.attached_deposit = storage_cost(ACCOUNT_STORAGE_BYTES + 1_000);
+        testing_env!(context_deposit.clone());
+        let balance = contract.storage_deposit(None, None);
+        assert_eq!(storage_cost(1_000), balance.available.0);
+        
+        testing_env!(context.clone());
+        contract.set_status("Hello".to_string(), None, None, None, None);
+        let used = contract.storage_accounts.get(&"bob_near".to_string()).unwrap().bytes_used;
+        assert!(used > 0);
+        assert_eq!(storage_cost(1_000 - used), contract.storage_balance_of("bob_near".to_string()).unwrap().available.0);
+        
+        let mut context_withdraw = context.clone();
+        context_withdraw.attached_deposit = 1;
+        context_withdraw.account_balance = 10u128.pow(25);
+        context_withdraw.storage_usage = env::storage_usage();
+        testing_env!(context_withdraw);
+        let balance = contract.storage_withdraw(None);
+        assert_eq!(0, balance.available.0);
+    }
+    
+    #[test]
+    #[should_panic(expected = "Insufficient storage balance")]
+    fn storage_balance_too_low() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = storage_cost(ACCOUNT_STORAGE_BYTES);
+        testing_env!(context.clone());
+        let mut contract = StatusMessage::default();
+        contract.storage_deposit(None, Some(true));
+        
+        context.attached_deposit = 0;
+        context.storage_usage = env::storage_usage();
+        testing_env!(context);
+        contract.set_status("Hello".to_string(), None, None, None, None);
+    }
+    
+    #[test]
+    fn deletions_release_storage() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let mut contract = StatusMessage::default();
+        let status_id = contract.set_status("Hello".to_string(), None, None, None, None);
+        contract.add_reaction("bob_near".to_string(), "like".to_string());
+        let bob = || "bob_near".to_string();
+        let used = contract.storage_accounts.get(&bob()).unwrap().bytes_used;
+        
+        let released = contract.clear_notifications();
+        assert!(released > 0);
+        assert!(contract.notifications.get(&bob()).is_none());
+        assert!(contract.storage_accounts.get(&bob()).unwrap().bytes_used < used);
+        assert_eq!(0, contract.clear_notifications());
+        
+        assert!(contract.delete_status(Some(true)) > 0);
+        assert!(contract.history.get(&bob()).is_none());
+        assert!(contract.reactions.get(&status_id).is_none());
+    }
+    
+    #[test]
+    fn freed_storage_goes_to_whoever_paid() {
+        let context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = StatusMessage::default();
+        let bob = || "bob_near".to_string();
+        let alice = || "alice_near".to_string();
+        contract.set_status("Hello".to_string(), None, None, None, None);
+        
+        let mut context_alice = context.clone();
+        context_alice.predecessor_account_id = alice();
+        context_alice.storage_usage = env::storage_usage();
+        testing_env!(context_alice);
+        contract.follow(bob());
+        contract.add_reaction(bob(), "like".to_string());
+        let bytes_used = |contract: &StatusMessage, account_id: String| contract.storage_accounts.get(&account_id).unwrap().bytes_used;
+        let (bob_used, alice_used) = (bytes_used(&contract, bob()), bytes_used(&contract, alice()));
+        
+        // Alice paid for the notifications, so clearing them cannot empty Bob's balance
+        let mut context_bob = context;
+        context_bob.storage_usage = env::storage_usage();
+        testing_env!(context_bob);
+        assert!(contract.clear_notifications() > 0);
+        assert_eq!(bob_used, bytes_used(&contract, bob()));
+        let alice_after_clear = bytes_used(&contract, alice());
+        assert!(alice_after_clear < alice_used);
+        
+        contract.delete_status(Some(true));
+        assert!(bytes_used(&contract, alice()) < alice_after_clear);
+        assert!(bytes_used(&contract, bob()) < bob_used);
+    }
+    
+    #[test]
+    #[should_panic(expected = "Account is not registered, call storage_deposit first")]
+    fn unregistered_account_cannot_store() {
+        let mut context = get_context(vec![], false);
+        context.attached_deposit = 0;
+        testing_env!(context);
+        let mut contract = StatusMessage::default();
+        contract.set_profile("Bob".to_string(), "Builder".to_string());
+    }
+    
+    #[test]
+    fn daily_activity() {
+        let mut context = get_context(vec![], false);
+        context.block_timestamp = 3 * NANOS_PER_DAY + 1;
+        testing_env!(context.clone());
+        let mut contract = StatusMessage::default();
+        contract.set_status("Morning".to_string(), None, None, None, None);
+        contract.set_status("Evening".to_string(), None, None, None, None);
+        
+        let mut context_later = context.clone();
+        context_later.block_timestamp = 5 * NANOS_PER_DAY;
+        context_later.storage_usage = env::storage_usage();
+        testing_env!(context_later);
+        contract.set_status("Next".to_string(), None, None, None, None);
+        
+        assert_eq!(vec![0, 2, 0, 1], contract.get_activity("bob_near".to_string(), 2, 5));
+        assert_eq!(vec![0], contract.get_activity("alice_near".to_string(), 3, 3));
+    }
+    
+    #[test]
+    fn batch_statuses_and_overview() {
+        let context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = StatusMessage::default();
+        contract.set_status("Hello".to_string(), Some(true), Some(2), None, None);
+        contract.set_profile("Bob".to_string(), "Builder".to_string());
+        contract.view_status("bob_near".to_string());
+        
+        let statuses = contract.get_statuses(vec!["bob_near".to_string(), "alice_near".to_string()], None);
+        assert_eq!("Hello", statuses[0].as_ref().unwrap().message);
+        assert!(statuses[1].is_none());
+        
+        let overview = contract.get_account_overview("bob_near".to_string(), None);
+        assert_eq!("Hello", overview.status.unwrap().message);
+        assert!(overview.expires_at.is_some());
+        assert_eq!("Bob", overview.profile.unwrap().name);
+        assert_eq!(0, overview.follower_count);
+        assert_eq!(1, overview.view_count);
+    }
+    
+    #[test]
+    #[should_panic(expected = "Cannot fetch more than 50 accounts at once")]
+    fn batch_statuses_cap() {
+        let context = get_context(vec![], false);
+        testing_env!(context);
+        let contract = StatusMessage::default();
+        contract.get_statuses(vec!["bob_near".to_string(); 51], None);
+    }
+    
+    #[test]
+    fn paginated_public_statuses() {
+        let context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = StatusMessage::default();
+        for account_id in vec!["alice_near", "bob_near", "carol_near"] {
+            let mut context_author = context.clone();
+            context_author.predecessor_account_id = account_id.to_string();
+            context_author.storage_usage = env::storage_usage();
+            testing_env!(context_author);
+            contract.set_status(format!("Hello from {}", account_id), Some(true), None, None, None);
+        }
+        
+        let page = contract.get_public_statuses(None, Some(2));
+        assert_eq!(3, page.total);
+        assert_eq!(vec!["alice_near", "bob_near"], page.statuses.iter().map(|(a, _)| a.as_str()).collect::<Vec<_>>());
+        let page = contract.get_public_statuses(page.next_index, Some(2));
+        assert_eq!("carol_near", page.statuses[0].0);
+        assert_eq!(None, page.next_index);
+        
+        let page = contract.search_status("hello".to_string(), None, Some(1));
+        assert_eq!("alice_near", page.statuses[0].0);
+        let page = contract.search_status("hello".to_string(), page.next_index, Some(5));
+        assert_eq!(2, page.statuses.len());
+        assert_eq!(None, page.next_index);
     }
     
     #[test]
@@ -523,11 +3146,11 @@ mod tests {
         let context = get_context(vec![], false);
         testing_env!(context.clone());
         let mut contract = StatusMessage::default();
-        contract.set_status("Hello world".to_string(), Some(true), None);
+        contract.set_status("Hello world".to_string(), Some(true), None, None, None);
         
-        // Change signer for alice
+        // Change caller to alice
         let mut context_alice = context.clone();
-        context_alice.signer_account_id = "alice_near".to_string();
+        context_alice.predecessor_account_id = "alice_near".to_string();
         testing_env!(context_alice);
         
         contract.add_reaction("bob_near".to_string(), "like".to_string());
@@ -537,13 +3160,50 @@ mod tests {
         assert_eq!("like", reactions[0].reaction_type);
     }
     
+    #[test]
+    fn reactions_belong_to_a_status() {
+        let context = get_context(vec![], false);
+        testing_env!(context.clone());
+        let mut contract = StatusMessage::default();
+        let bob = || "bob_near".to_string();
+        let first_id = contract.set_status("First".to_string(), None, None, None, None);
+        
+        let mut context_alice = context.clone();
+        context_alice.predecessor_account_id = "alice_near".to_string();
+        context_alice.storage_usage = env::storage_usage();
+        testing_env!(context_alice.clone());
+        contract.add_reaction(bob(), "like".to_string());
+        contract.add_reaction(bob(), "love".to_string());
+        assert_eq!(1, contract.get_reactions(bob()).len());
+        
+        let mut context_bob = context;
+        context_bob.storage_usage = env::storage_usage();
+        testing_env!(context_bob.clone());
+        let second_id = contract.set_status("Second".to_string(), None, None, None, None);
+        context_alice.storage_usage = env::storage_usage();
+        testing_env!(context_alice);
+        // Reacting to the first status does not stop alice reacting to the second
+        contract.add_reaction(bob(), "love".to_string());
+        let reactions = contract.get_reactions(bob());
+        assert_eq!(Some(second_id), reactions[0].status_id);
+        assert_eq!("love", reactions[0].reaction_type);
+        assert_eq!("like", contract.reactions.get(&first_id).unwrap().get(&"alice_near".to_string()).unwrap().reaction_type);
+        
+        // Deleting a status removes only its own reactions
+        context_bob.storage_usage = env::storage_usage();
+        testing_env!(context_bob);
+        contract.set_retention_policy(Some(RetentionPolicy::KeepLast(1)));
+        assert!(contract.reactions.get(&first_id).is_none());
+        assert_eq!(1, contract.get_reactions(bob()).len());
+    }
+    
     #[test]
     fn notifications() {
         let context = get_context(vec![], false);
         testing_env!(context);
         let mut contract = StatusMessage::default();
         
-        contract.add_notification("bob_near", "Test notification".to_string());
+        contract.add_notification("bob_near", "Test notification".to_string(), None);
         let notifications = contract.get_notifications();
         assert_eq!(1, notifications.len());
         assert_eq!("Test notification", notifications[0]);
@@ -568,13 +3228,13 @@ mod tests {
         let mut contract = StatusMessage::default();
         
         // Set status with 1 hour expiry
-        contract.set_status("Temporary status".to_string(), Some(true), Some(1));
+        contract.set_status("Temporary status".to_string(), Some(true), Some(1), None, None);
         
         // Status should be available initially
-        assert_eq!("Temporary status", contract.get_status("bob_near".to_string()).unwrap());
+        assert_eq!("Temporary status", contract.get_status("bob_near".to_string(), None).unwrap().message);
         
         // Simulate time passing (this is a simplified test)
-        let history = contract.get_status_history("bob_near".to_string());
+        let history = contract.get_status_history("bob_near".to_string(), None, None, None, None).records;
         assert_eq!(1, history.len());
     }
 }
\ No newline at end of file
@@ -0,0 +1,44 @@
+//! Mentions module
+use near_sdk::env;
+
+/// Upper bound on mentions processed per status, keeps notification fan-out bounded.
+pub const MAX_MENTIONS_PER_STATUS: usize = 10;
+
+fn is_account_char(c: char) -> bool {
+    c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_' || c == '.'
+}
+
+/// Extracts unique `@account_id` mentions in order of appearance, skipping invalid account ids.
+pub fn extract_mentions(message: &str) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    let mut previous = ' ';
+    let mut chars = message.char_indices().peekable();
+    
+    while let Some((i, c)) = chars.next() {
+        if c == '@' && !previous.is_ascii_alphanumeric() {
+            let start = i + 1;
+            let mut end = start;
+            while let Some(&(j, next)) = chars.peek() {
+                if !is_account_char(next) {
+                    break;
+                }
+                end = j + next.len_utf8();
+                chars.next();
+            }
+            
+            // Trailing punctuation such as "@alice.near." is not part of the account id
+            let candidate = message[start..end].trim_end_matches(|c| c == '.' || c == '-' || c == '_');
+            if env::is_valid_account_id(candidate.as_bytes()) && !result.iter().any(|m| m == candidate) {
+                result.push(candidate.to_string());
+                if result.len() == MAX_MENTIONS_PER_STATUS {
+                    break;
+                }
+            }
+            previous = ' ';
+            continue;
+        }
+        previous = c;
+    }
+    
+    result
+}
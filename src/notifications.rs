@@ -2,6 +2,14 @@
 use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::collections::Vector;
 
+/// A notification and the account whose storage balance paid for it. Notifications the
+/// contract wrote itself have no payer.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Notification {
+    pub message: String,
+    pub paid_by: Option<String>,
+}
+
 pub struct NotificationManager {
     pub notifications: Vector<String>,
 }
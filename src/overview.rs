@@ -0,0 +1,23 @@
+//! Account overview module
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::history::StatusRecord;
+use crate::profile::UserProfile;
+
+/// Largest number of accounts a single batch view may ask for.
+pub const MAX_BATCH_SIZE: usize = 50;
+
+/// Everything a dashboard shows for one account, fetched in a single call.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountOverview {
+    pub account_id: String,
+    pub status: Option<StatusRecord>,
+    pub expires_at: Option<u64>,
+    pub profile: Option<UserProfile>,
+    pub follower_count: u64,
+    pub following_count: u64,
+    pub reaction_counts: HashMap<String, u64>,
+    pub view_count: u64,
+}
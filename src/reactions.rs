@@ -8,4 +8,5 @@ pub struct Reaction {
     pub account_id: String,
     pub reaction_type: String, // "like", "love", "laugh", etc.
     pub timestamp: u64,
+    pub status_id: Option<String>, // the status that was current when reacting
 }
\ No newline at end of file
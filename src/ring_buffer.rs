@@ -0,0 +1,73 @@
+//! Ring buffer module
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::env;
+
+/// A persistent queue that can drop elements from the front in constant time. Elements are
+/// stored under a moving head, so indexes passed to `get` and `replace` count from the
+/// oldest element still in the buffer.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct RingBuffer<T> {
+    head: u64,
+    len: u64,
+    elements: LookupMap<u64, T>,
+}
+
+impl<T: BorshSerialize + BorshDeserialize> RingBuffer<T> {
+    pub fn new(prefix: Vec<u8>) -> Self {
+        Self { head: 0, len: 0, elements: LookupMap::new(prefix) }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn key(&self, index: u64) -> u64 {
+        self.head.wrapping_add(index)
+    }
+
+    pub fn get(&self, index: u64) -> Option<T> {
+        if index >= self.len {
+            return None;
+        }
+        self.elements.get(&self.key(index))
+    }
+
+    pub fn replace(&mut self, index: u64, element: &T) {
+        if index >= self.len {
+            env::panic_str("Index out of bounds");
+        }
+        self.elements.insert(&self.key(index), element);
+    }
+
+    pub fn push_back(&mut self, element: &T) {
+        self.elements.insert(&self.key(self.len), element);
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let element = self.elements.remove(&self.head);
+        self.head = self.head.wrapping_add(1);
+        self.len -= 1;
+        element
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        (0..self.len).map(move |index| self.get(index).unwrap())
+    }
+
+    pub fn clear(&mut self) {
+        for index in 0..self.len {
+            self.elements.remove(&self.key(index));
+        }
+        self.head = 0;
+        self.len = 0;
+    }
+}
@@ -0,0 +1,44 @@
+//! Scheduled statuses module
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::attachments::Attachment;
+use crate::history::StatusRecord;
+use crate::visibility::Visibility;
+
+/// Upper bound on pending scheduled statuses per account, keeps publishing gas bounded.
+pub const MAX_SCHEDULED_PER_ACCOUNT: u64 = 10;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ScheduledStatus {
+    pub id: u64,
+    pub message: String,
+    pub publish_at: u64,
+    pub visibility: Visibility,
+    pub expires_in_hours: Option<u64>,
+    pub attachments: Vec<Attachment>,
+}
+
+impl ScheduledStatus {
+    pub fn is_due(&self, now: u64) -> bool {
+        now >= self.publish_at
+    }
+    
+    pub fn expires_at(&self) -> Option<u64> {
+        match self.expires_in_hours {
+            Some(hours) if hours > 0 => Some(self.publish_at + (hours * 3600 * 1_000_000_000)),
+            _ => None,
+        }
+    }
+    
+    /// The record this status becomes once published under `seq`. It is stamped with the
+    /// time it is actually published, which may be after `publish_at`, so timelines and
+    /// history stay in timestamp order.
+    pub fn to_record(&self, account_id: &str, seq: u64) -> StatusRecord {
+        let mut record = StatusRecord::new(account_id, seq, self.message.clone(), self.expires_at());
+        record.attachments = self.attachments.clone();
+        record.visibility = self.visibility.clone();
+        record
+    }
+}
@@ -0,0 +1,51 @@
+//! Storage management module (NEP-145)
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::{env, Balance};
+use serde::{Deserialize, Serialize};
+
+/// Bytes reserved for an account's own storage entry; their cost is the minimum balance.
+pub const ACCOUNT_STORAGE_BYTES: u64 = 200;
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalance {
+    pub total: U128,
+    pub available: U128,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalanceBounds {
+    pub min: U128,
+    pub max: Option<U128>,
+}
+
+/// What an account has deposited and how many bytes of contract state it pays for.
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct StorageAccount {
+    pub deposit: Balance,
+    pub bytes_used: u64,
+}
+
+impl StorageAccount {
+    /// Deposit needed to cover the account's entry and everything it has stored.
+    pub fn required(&self) -> Balance {
+        storage_cost(ACCOUNT_STORAGE_BYTES + self.bytes_used)
+    }
+    
+    pub fn available(&self) -> Balance {
+        self.deposit.saturating_sub(self.required())
+    }
+    
+    pub fn balance(&self) -> StorageBalance {
+        StorageBalance {
+            total: U128(self.deposit),
+            available: U128(self.available()),
+        }
+    }
+}
+
+pub fn storage_cost(bytes: u64) -> Balance {
+    Balance::from(bytes) * env::storage_byte_cost()
+}
@@ -0,0 +1,48 @@
+//! Hashtags module
+
+/// Upper bound on tags indexed per status, keeps index writes bounded.
+pub const MAX_TAGS_PER_STATUS: usize = 10;
+pub const MAX_TAG_LENGTH: usize = 64;
+
+fn is_tag_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Normalizes a tag for indexing: strips a leading `#` and lowercases it.
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim_start_matches('#').to_lowercase()
+}
+
+/// Extracts unique, normalized `#tags` in order of appearance.
+pub fn extract_tags(message: &str) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    let mut previous = ' ';
+    let mut chars = message.char_indices().peekable();
+    
+    while let Some((i, c)) = chars.next() {
+        if c == '#' && !is_tag_char(previous) {
+            let start = i + 1;
+            let mut end = start;
+            while let Some(&(j, next)) = chars.peek() {
+                if !is_tag_char(next) {
+                    break;
+                }
+                end = j + next.len_utf8();
+                chars.next();
+            }
+            
+            let tag = normalize_tag(&message[start..end]);
+            if !tag.is_empty() && tag.len() <= MAX_TAG_LENGTH && !result.contains(&tag) {
+                result.push(tag);
+                if result.len() == MAX_TAGS_PER_STATUS {
+                    break;
+                }
+            }
+            previous = ' ';
+            continue;
+        }
+        previous = c;
+    }
+    
+    result
+}
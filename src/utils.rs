@@ -0,0 +1,20 @@
+//! Collection helpers
+use near_sdk::borsh::BorshDeserialize;
+use near_sdk::collections::Vector;
+use near_sdk::env;
+
+/// Storage prefix for a collection nested under `key`: a one-byte collection tag followed by
+/// the SHA-256 of the key. Every such prefix has the same length, so none can extend another.
+pub fn nested_prefix(tag: u8, key: &str) -> Vec<u8> {
+    let mut prefix = vec![tag];
+    prefix.extend(env::sha256(key.as_bytes()));
+    prefix
+}
+
+/// Elements of `vector` that still deserialize as `T`. Entries overwritten with something
+/// else are skipped.
+pub fn recover<T: BorshDeserialize>(vector: &Vector<T>) -> Vec<T> {
+    vector.iter_raw()
+        .filter_map(|raw| T::try_from_slice(&raw).ok())
+        .collect()
+}
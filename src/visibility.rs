@@ -0,0 +1,39 @@
+//! Status visibility module
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+/// Who a status is shown to. The author always sees their own statuses.
+///
+/// This is a display filter, not access control: views take the reader as an unverified
+/// `viewer_id`, and all contract state is readable on chain.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Visibility {
+    Public,
+    FollowersOnly,
+    MutualsOnly,
+    Private,
+    List(String), // name of one of the author's custom lists
+}
+
+impl Visibility {
+    /// Resolves the visibility of a new status, falling back to the legacy `is_public` flag.
+    pub fn resolve(visibility: Option<Visibility>, is_public: Option<bool>) -> Self {
+        match (visibility, is_public) {
+            (Some(visibility), _) => visibility,
+            (None, Some(false)) => Visibility::Private,
+            (None, _) => Visibility::Public,
+        }
+    }
+}
+
+impl Default for Visibility {
+    fn default() -> Self {
+        Visibility::Public
+    }
+}
+
+/// Storage key of a custom list: lists are namespaced by their owner.
+pub fn list_key(owner_id: &str, name: &str) -> String {
+    format!("{}:{}", owner_id, name)
+}
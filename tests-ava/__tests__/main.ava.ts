@@ -15,10 +15,10 @@ const workspace = Workspace.init(async ({root}) => {
   return {alice, contract};
 });
 workspace.test('set get message', async(test, {alice, contract, root})=>{
-  await alice.call(contract, 'set_status', {message: 'hello'});
-  const aliceStatus = await contract.view('get_status', {account_id: alice});
+  await alice.call(contract, 'set_status', {message: 'hello'}, {attachedDeposit: '10000000000000000000000'});
+  const aliceStatus: any = await contract.view('get_status', {account_id: alice});
 
-  test.is(aliceStatus, 'hello');
+  test.is(aliceStatus.message, 'hello');
 });
 
 workspace.test('get nonexistent message', async (test, {alice, contract, root})=>{